@@ -2,9 +2,9 @@ use soroban_env_macros::generate_call_macro_with_all_host_functions;
 
 use super::Symbol;
 use super::{
-    AddressObject, Bool, BytesObject, I128Object, I256Object, I64Object, MapObject, Object, RawVal,
-    Status, StringObject, SymbolObject, U128Object, U256Object, U32Val, U64Object, U64Val,
-    VecObject, Void,
+    AddressObject, Bool, BytesObject, I128Object, I128Val, I256Object, I256Val, I64Object,
+    MapObject, Object, RawVal, Status, StringObject, SymbolObject, U128Object, U128Val, U256Object,
+    U256Val, U32Val, U64Object, U64Val, VecObject, Void,
 };
 use core::any;
 
@@ -42,7 +42,7 @@ pub const ENV_META_V0_SECTION_NAME: &'static str = "contractenvmetav0";
 
 soroban_env_macros::generate_env_meta_consts!(
     ledger_protocol_version: 20,
-    pre_release_version: 37,
+    pre_release_version: 38,
 );
 
 pub fn get_ledger_protocol_version(interface_version: u64) -> u32 {
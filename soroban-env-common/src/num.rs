@@ -317,6 +317,149 @@ pub fn i256_into_pieces(i: I256) -> (i64, u64, u64, u64) {
     (hi_hi, hi_lo, lo_hi, lo_lo)
 }
 
+/// The rounding applied to the results of [i128_mul_div] and [u256_mul_div],
+/// which is passed to the `mul_div` host functions as a `u32`.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RoundingMode {
+    /// Round towards negative infinity.
+    Floor = 0,
+    /// Round towards positive infinity.
+    Ceil = 1,
+    /// Round to the nearest integer, and to the even one on ties.
+    HalfEven = 2,
+}
+
+impl TryFrom<u32> for RoundingMode {
+    type Error = ConversionError;
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(RoundingMode::Floor),
+            1 => Ok(RoundingMode::Ceil),
+            2 => Ok(RoundingMode::HalfEven),
+            _ => Err(ConversionError),
+        }
+    }
+}
+
+impl From<RoundingMode> for U32Val {
+    fn from(value: RoundingMode) -> Self {
+        (value as u32).into()
+    }
+}
+
+impl RoundingMode {
+    // Whether an inexact quotient, truncated towards zero, is to be rounded
+    // away from zero. `half` is the ordering of the remainder relative to
+    // half the divisor, in absolute values.
+    fn rounds_away(self, negative: bool, half: Ordering, odd: bool) -> bool {
+        match self {
+            RoundingMode::Floor => negative,
+            RoundingMode::Ceil => !negative,
+            RoundingMode::HalfEven => match half {
+                Ordering::Less => false,
+                Ordering::Equal => odd,
+                Ordering::Greater => true,
+            },
+        }
+    }
+}
+
+/// Computes `a * b / c` rounded according to `mode`, without overflowing on
+/// the intermediate product. Returns `None` if `c` is zero or if the result
+/// doesn't fit in an `i128`.
+pub fn i128_mul_div(a: i128, b: i128, c: i128, mode: RoundingMode) -> Option<i128> {
+    if c == 0 {
+        return None;
+    }
+    // The product of two i128s always fits in an I256.
+    let p = I256::from(a) * I256::from(b);
+    let c = I256::from(c);
+    let (mut q, r) = (p / c, p % c);
+    if r != I256::ZERO {
+        let negative = (p < I256::ZERO) != (c < I256::ZERO);
+        // |r| < |c| <= 2^127, so doubling it can't overflow.
+        let half = (r.abs() * 2).cmp(&c.abs());
+        let odd = q & I256::ONE != I256::ZERO;
+        if mode.rounds_away(negative, half, odd) {
+            q += if negative { I256::MINUS_ONE } else { I256::ONE };
+        }
+    }
+    if q < I256::from(i128::MIN) || q > I256::from(i128::MAX) {
+        return None;
+    }
+    Some(q.as_i128())
+}
+
+/// Computes `a * b / c` rounded according to `mode`, carrying the
+/// intermediate product on 512 bits. Returns `None` if `c` is zero or if the
+/// result doesn't fit in a `U256`.
+pub fn u256_mul_div(a: U256, b: U256, c: U256, mode: RoundingMode) -> Option<U256> {
+    if c == U256::ZERO {
+        return None;
+    }
+    let (hi, lo) = u256_widening_mul(a, b);
+    // The quotient only fits in 256 bits if the high half of the product is
+    // smaller than the divisor.
+    if hi >= c {
+        return None;
+    }
+    let (q, r) = if hi == U256::ZERO {
+        (lo / c, lo % c)
+    } else {
+        u512_div_rem(hi, lo, c)
+    };
+    if r == U256::ZERO {
+        return Some(q);
+    }
+    // r < c, so comparing r with c - r compares it with half of c without
+    // overflowing.
+    let half = r.cmp(&(c - r));
+    let odd = q & U256::ONE != U256::ZERO;
+    if mode.rounds_away(false, half, odd) {
+        q.checked_add(U256::ONE)
+    } else {
+        Some(q)
+    }
+}
+
+// Returns the high and the low halves of the 512-bit product of `a` and `b`.
+fn u256_widening_mul(a: U256, b: U256) -> (U256, U256) {
+    let (a_hi, a_lo) = a.into_words();
+    let (b_hi, b_lo) = b.into_words();
+    // The products of 128-bit words always fit in 256 bits.
+    let ll = U256::from(a_lo) * U256::from(b_lo);
+    let lh = U256::from(a_lo) * U256::from(b_hi);
+    let hl = U256::from(a_hi) * U256::from(b_lo);
+    let hh = U256::from(a_hi) * U256::from(b_hi);
+    let (lo, carry1) = ll.overflowing_add(lh << 128);
+    let (lo, carry2) = lo.overflowing_add(hl << 128);
+    let hi = hh + (lh >> 128) + (hl >> 128) + U256::new(carry1 as u128 + carry2 as u128);
+    (hi, lo)
+}
+
+// Divides the 512-bit `hi:lo` by `c` one bit at a time, returning the
+// quotient and the remainder. Requires `hi < c`, which makes the quotient fit
+// in 256 bits.
+// The host charges the steps of this loop (see `u256_mul_div_costs`), which
+// has to be kept in line with them.
+fn u512_div_rem(hi: U256, lo: U256, c: U256) -> (U256, U256) {
+    let mut r = hi;
+    let mut q = U256::ZERO;
+    for i in (0..256).rev() {
+        // The remainder is shifted out of 256 bits when its top bit is set,
+        // in which case it's larger than `c`.
+        let carry = r >> 255 != U256::ZERO;
+        r = (r << 1) | ((lo >> i) & U256::ONE);
+        q <<= 1;
+        if carry || r >= c {
+            r = r.wrapping_sub(c);
+            q |= U256::ONE;
+        }
+    }
+    (q, r)
+}
+
 pub const MIN_SMALL_U64: u64 = 0;
 pub const MAX_SMALL_U64: u64 = 0x00ff_ffff_ffff_ffff_u64;
 
@@ -382,3 +525,71 @@ fn test_small_ints() {
     assert!(is_small_i256(&I256::new(1_i128)));
     assert!(is_small_i256(&I256::new(12345_i128)));
 }
+
+#[test]
+fn test_mul_div() {
+    use RoundingMode::*;
+
+    assert_eq!(i128_mul_div(7, 3, 2, Floor), Some(10));
+    assert_eq!(i128_mul_div(7, 3, 2, Ceil), Some(11));
+    assert_eq!(i128_mul_div(7, 3, 2, HalfEven), Some(10));
+    assert_eq!(i128_mul_div(-7, 3, 2, Floor), Some(-11));
+    assert_eq!(i128_mul_div(-7, 3, 2, Ceil), Some(-10));
+    assert_eq!(i128_mul_div(-7, 3, 2, HalfEven), Some(-10));
+    assert_eq!(i128_mul_div(5, 3, -2, HalfEven), Some(-8));
+    assert_eq!(i128_mul_div(10, 1, 4, HalfEven), Some(2));
+    assert_eq!(i128_mul_div(11, 1, 4, HalfEven), Some(3));
+    assert_eq!(
+        i128_mul_div(i128::MAX, i128::MAX, i128::MAX, Floor),
+        Some(i128::MAX)
+    );
+    assert_eq!(
+        i128_mul_div(i128::MIN, i128::MIN, i128::MIN, Ceil),
+        Some(i128::MIN)
+    );
+    assert_eq!(i128_mul_div(i128::MAX, 2, 1, Floor), None);
+    assert_eq!(i128_mul_div(i128::MIN, -1, 1, Floor), None);
+    assert_eq!(i128_mul_div(1, 1, 0, Floor), None);
+
+    let two = U256::new(2);
+    assert_eq!(
+        u256_mul_div(U256::new(7), U256::new(3), two, Floor),
+        Some(U256::new(10))
+    );
+    assert_eq!(
+        u256_mul_div(U256::new(7), U256::new(3), two, Ceil),
+        Some(U256::new(11))
+    );
+    assert_eq!(
+        u256_mul_div(U256::new(7), U256::new(3), two, HalfEven),
+        Some(U256::new(10))
+    );
+    assert_eq!(
+        u256_mul_div(U256::new(9), U256::ONE, two, HalfEven),
+        Some(U256::new(4))
+    );
+    assert_eq!(
+        u256_mul_div(U256::MAX, U256::MAX, U256::MAX, Floor),
+        Some(U256::MAX)
+    );
+    assert_eq!(
+        u256_mul_div(U256::MAX, U256::MAX, U256::MAX - 1, Floor),
+        None
+    );
+    assert_eq!(u256_mul_div(U256::MAX, two, U256::MAX, Ceil), Some(two));
+    // (2^256 - 1) * 3 / 4 = 3 * 2^254 - 3/4
+    assert_eq!(
+        u256_mul_div(U256::MAX, U256::new(3), U256::new(4), Floor),
+        Some((U256::new(3) << 254) - 1)
+    );
+    assert_eq!(
+        u256_mul_div(U256::MAX, U256::new(3), U256::new(4), HalfEven),
+        Some(U256::new(3) << 254)
+    );
+    assert_eq!(
+        u256_mul_div(U256::MAX, U256::MAX, U256::MAX, Ceil),
+        Some(U256::MAX)
+    );
+    assert_eq!(u256_mul_div(U256::MAX, U256::new(3), two, Floor), None);
+    assert_eq!(u256_mul_div(U256::ONE, U256::ONE, U256::ZERO, Floor), None);
+}
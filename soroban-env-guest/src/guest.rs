@@ -6,9 +6,9 @@ use core::convert::Infallible;
 use soroban_env_common::call_macro_with_all_host_functions;
 
 use super::{
-    AddressObject, Bool, BytesObject, I128Object, I256Object, I64Object, MapObject, Object, RawVal,
-    Status, StringObject, SymbolObject, U128Object, U256Object, U32Val, U64Object, U64Val,
-    VecObject, Void,
+    AddressObject, Bool, BytesObject, I128Object, I128Val, I256Object, I256Val, I64Object,
+    MapObject, Object, RawVal, Status, StringObject, SymbolObject, U128Object, U128Val, U256Object,
+    U256Val, U32Val, U64Object, U64Val, VecObject, Void,
 };
 use super::{Env, EnvBase, Symbol};
 #[cfg(target_family = "wasm")]
@@ -514,7 +514,7 @@ macro_rules! generate_extern_modules {
                 #[allow(unused_imports)]
                 use crate::{RawVal,Object,Symbol,Status,MapObject,VecObject,BytesObject};
                 #[allow(unused_imports)]
-                use crate::{I128Object, I256Object, I64Object, I64Val, U128Object, U256Object, U32Val, U64Object, U64Val};
+                use crate::{I128Object, I128Val, I256Object, I256Val, I64Object, I64Val, U128Object, U128Val, U256Object, U256Val, U32Val, U64Object, U64Val};
                 #[allow(unused_imports)]
                 use crate::{Void,AddressObject,SymbolObject,StringObject,Bool};
                 #[link(wasm_import_module = $mod_str)]
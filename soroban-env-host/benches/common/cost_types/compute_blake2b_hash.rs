@@ -0,0 +1,19 @@
+use crate::common::HostCostMeasurement;
+use rand::rngs::StdRng;
+use soroban_env_host::{cost_runner::ComputeBlake2bHashRun, Host};
+
+// This measures the costs of performing a blake2b hash on a variable-sized
+// byte buffer. The input value is the size of the buffer. It should be
+// linear time.
+pub(crate) struct ComputeBlake2bHashMeasure;
+
+impl HostCostMeasurement for ComputeBlake2bHashMeasure {
+    type Runner = ComputeBlake2bHashRun;
+
+    const STEP_SIZE: u64 = 100;
+
+    fn new_random_case(_host: &Host, _rng: &mut StdRng, input: u64) -> Vec<u8> {
+        let size = 1 + input * Self::STEP_SIZE;
+        (0..size).map(|n| n as u8).collect()
+    }
+}
@@ -0,0 +1,23 @@
+use crate::common::HostCostMeasurement;
+use k256::ecdsa::SigningKey;
+use rand::{rngs::StdRng, Rng};
+use soroban_env_host::{cost_runner::ComputeSecp256k1PubKeyRun, Host};
+
+// This measures the costs to decode a secp256k1 pubkey from its SEC1
+// encoding, which should be constant time. The compressed encoding is the
+// worst case, as the `y` coordinate has to be recomputed from `x`. The input
+// value is ignored.
+pub(crate) struct ComputeSecp256k1PubKeyMeasure;
+
+impl HostCostMeasurement for ComputeSecp256k1PubKeyMeasure {
+    type Runner = ComputeSecp256k1PubKeyRun;
+
+    fn new_random_case(_host: &Host, rng: &mut StdRng, _input: u64) -> Vec<u8> {
+        let signing_key = SigningKey::from_slice(&rng.gen::<[u8; 32]>()).unwrap();
+        signing_key
+            .verifying_key()
+            .to_encoded_point(true)
+            .as_bytes()
+            .to_vec()
+    }
+}
@@ -0,0 +1,56 @@
+use crate::common::HostCostMeasurement;
+use rand::{rngs::StdRng, Rng};
+use soroban_env_host::{
+    cost_runner::{Int256AddSubRun, Int256DivRun, Int256MulRun, Int256ShiftRun},
+    Host, I256,
+};
+
+fn rand_i256(rng: &mut StdRng) -> I256 {
+    I256::from_words(rng.gen(), rng.gen())
+}
+
+// These measure the costs of the arithmetic on 256-bit integers, which
+// should be constant time. The input values are ignored.
+
+pub(crate) struct Int256AddSubMeasure;
+
+impl HostCostMeasurement for Int256AddSubMeasure {
+    type Runner = Int256AddSubRun;
+
+    fn new_random_case(_host: &Host, rng: &mut StdRng, _input: u64) -> (I256, I256) {
+        (rand_i256(rng), rand_i256(rng))
+    }
+}
+
+pub(crate) struct Int256MulMeasure;
+
+impl HostCostMeasurement for Int256MulMeasure {
+    type Runner = Int256MulRun;
+
+    fn new_random_case(_host: &Host, rng: &mut StdRng, _input: u64) -> (I256, I256) {
+        (rand_i256(rng), rand_i256(rng))
+    }
+}
+
+pub(crate) struct Int256DivMeasure;
+
+// A divisor on fewer words than the dividend makes for the longest
+// divisions.
+impl HostCostMeasurement for Int256DivMeasure {
+    type Runner = Int256DivRun;
+
+    fn new_random_case(_host: &Host, rng: &mut StdRng, _input: u64) -> (I256, I256) {
+        let divisor = I256::from_words(0, rng.gen::<i128>() | 1);
+        (rand_i256(rng), divisor)
+    }
+}
+
+pub(crate) struct Int256ShiftMeasure;
+
+impl HostCostMeasurement for Int256ShiftMeasure {
+    type Runner = Int256ShiftRun;
+
+    fn new_random_case(_host: &Host, rng: &mut StdRng, _input: u64) -> (I256, u32) {
+        (rand_i256(rng), rng.gen_range(0..256))
+    }
+}
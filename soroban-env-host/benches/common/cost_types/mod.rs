@@ -1,34 +1,52 @@
 mod charge_budget;
+mod compute_blake2b_hash;
 mod compute_ed25519_pubkey;
+mod compute_keccak256_hash;
+mod compute_secp256k1_pubkey;
+mod compute_secp256r1_pubkey;
 mod compute_sha256_hash;
+mod compute_sha512_hash;
 mod guard_frame;
 mod host_mem_alloc;
 mod host_mem_cmp;
 mod host_mem_cpy;
+mod int256_ops;
 mod invoke;
 mod map_ops;
+mod recover_ecdsa_secp256k1_key;
 mod val_deser;
 mod val_ser;
 mod val_xdr_conv;
 mod vec_ops;
+mod verify_ecdsa_secp256k1_sig;
+mod verify_ecdsa_secp256r1_sig;
 mod verify_ed25519_sig;
 mod visit_object;
 mod vm_ops;
 mod wasm_insn_exec;
 
 pub(crate) use charge_budget::*;
+pub(crate) use compute_blake2b_hash::*;
 pub(crate) use compute_ed25519_pubkey::*;
+pub(crate) use compute_keccak256_hash::*;
+pub(crate) use compute_secp256k1_pubkey::*;
+pub(crate) use compute_secp256r1_pubkey::*;
 pub(crate) use compute_sha256_hash::*;
+pub(crate) use compute_sha512_hash::*;
 pub(crate) use guard_frame::*;
 pub(crate) use host_mem_alloc::*;
 pub(crate) use host_mem_cmp::*;
 pub(crate) use host_mem_cpy::*;
+pub(crate) use int256_ops::*;
 pub(crate) use invoke::*;
 pub(crate) use map_ops::*;
+pub(crate) use recover_ecdsa_secp256k1_key::*;
 pub(crate) use val_deser::*;
 pub(crate) use val_ser::*;
 pub(crate) use val_xdr_conv::*;
 pub(crate) use vec_ops::*;
+pub(crate) use verify_ecdsa_secp256k1_sig::*;
+pub(crate) use verify_ecdsa_secp256r1_sig::*;
 pub(crate) use verify_ed25519_sig::*;
 pub(crate) use visit_object::*;
 pub(crate) use vm_ops::*;
@@ -0,0 +1,31 @@
+use crate::common::HostCostMeasurement;
+use k256::ecdsa::SigningKey;
+use rand::{rngs::StdRng, Rng};
+use soroban_env_host::{
+    cost_runner::{RecoverEcdsaSecp256k1KeyRun, RecoverEcdsaSecp256k1KeySample},
+    Host,
+};
+
+pub(crate) struct RecoverEcdsaSecp256k1KeyMeasure;
+
+// This measures the cost of recovering the public key from an ECDSA
+// secp256k1 signature of a 32-byte digest, which should be constant time. The
+// input value is ignored.
+impl HostCostMeasurement for RecoverEcdsaSecp256k1KeyMeasure {
+    type Runner = RecoverEcdsaSecp256k1KeyRun;
+
+    fn new_random_case(
+        _host: &Host,
+        rng: &mut StdRng,
+        _input: u64,
+    ) -> RecoverEcdsaSecp256k1KeySample {
+        let signing_key = SigningKey::from_slice(&rng.gen::<[u8; 32]>()).unwrap();
+        let digest: [u8; 32] = rng.gen();
+        let (sig, recovery_id) = signing_key.sign_prehash_recoverable(&digest).unwrap();
+        RecoverEcdsaSecp256k1KeySample {
+            digest,
+            sig,
+            recovery_id,
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use crate::common::HostCostMeasurement;
+use k256::ecdsa::SigningKey;
+use rand::{rngs::StdRng, Rng};
+use soroban_env_host::{
+    cost_runner::{VerifyEcdsaSecp256k1SigRun, VerifyEcdsaSecp256k1SigSample},
+    Host,
+};
+
+pub(crate) struct VerifyEcdsaSecp256k1SigMeasure;
+
+// This measures the cost of verifying an ECDSA secp256k1 signature of a
+// 32-byte digest, which should be constant time. The input value is ignored.
+impl HostCostMeasurement for VerifyEcdsaSecp256k1SigMeasure {
+    type Runner = VerifyEcdsaSecp256k1SigRun;
+
+    fn new_random_case(
+        _host: &Host,
+        rng: &mut StdRng,
+        _input: u64,
+    ) -> VerifyEcdsaSecp256k1SigSample {
+        let signing_key = SigningKey::from_slice(&rng.gen::<[u8; 32]>()).unwrap();
+        let digest: [u8; 32] = rng.gen();
+        let (sig, _) = signing_key.sign_prehash_recoverable(&digest).unwrap();
+        let key = *signing_key.verifying_key();
+        VerifyEcdsaSecp256k1SigSample { key, digest, sig }
+    }
+}
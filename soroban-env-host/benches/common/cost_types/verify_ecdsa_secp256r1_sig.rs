@@ -0,0 +1,29 @@
+use crate::common::HostCostMeasurement;
+use p256::ecdsa::{signature::hazmat::PrehashSigner, Signature, SigningKey};
+use rand::{rngs::StdRng, Rng};
+use soroban_env_host::{
+    cost_runner::{VerifyEcdsaSecp256r1SigRun, VerifyEcdsaSecp256r1SigSample},
+    Host,
+};
+
+pub(crate) struct VerifyEcdsaSecp256r1SigMeasure;
+
+// This measures the cost of verifying an ECDSA secp256r1 signature of a
+// 32-byte digest, which should be constant time. The input value is ignored.
+impl HostCostMeasurement for VerifyEcdsaSecp256r1SigMeasure {
+    type Runner = VerifyEcdsaSecp256r1SigRun;
+
+    fn new_random_case(
+        _host: &Host,
+        rng: &mut StdRng,
+        _input: u64,
+    ) -> VerifyEcdsaSecp256r1SigSample {
+        let signing_key = SigningKey::from_slice(&rng.gen::<[u8; 32]>()).unwrap();
+        let digest: [u8; 32] = rng.gen();
+        let sig: Signature = signing_key.sign_prehash(&digest).unwrap();
+        // The host only accepts the signatures normalized to low `s`.
+        let sig = sig.normalize_s().unwrap_or(sig);
+        let key = *signing_key.verifying_key();
+        VerifyEcdsaSecp256r1SigSample { key, digest, sig }
+    }
+}
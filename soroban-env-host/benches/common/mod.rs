@@ -10,8 +10,8 @@ pub use measure::*;
 pub use modelfit::*;
 
 use soroban_env_host::{
+    budget::CostType,
     cost_runner::{CostRunner, WasmInsnType},
-    xdr::ContractCostType,
 };
 use std::collections::BTreeMap;
 
@@ -50,7 +50,7 @@ fn should_run_wasm_insn(ty: WasmInsnType) -> bool {
 }
 
 fn call_bench<B: Benchmark, HCM: HostCostMeasurement>(
-    params: &mut BTreeMap<ContractCostType, (FPCostModel, FPCostModel)>,
+    params: &mut BTreeMap<CostType, (FPCostModel, FPCostModel)>,
 ) -> std::io::Result<()> {
     if should_run::<HCM>() {
         params.insert(<HCM::Runner as CostRunner>::COST_TYPE, B::bench::<HCM>()?);
@@ -59,12 +59,20 @@ fn call_bench<B: Benchmark, HCM: HostCostMeasurement>(
 }
 
 pub(crate) fn for_each_host_cost_measurement<B: Benchmark>(
-) -> std::io::Result<BTreeMap<ContractCostType, (FPCostModel, FPCostModel)>> {
-    let mut params: BTreeMap<ContractCostType, (FPCostModel, FPCostModel)> = BTreeMap::new();
+) -> std::io::Result<BTreeMap<CostType, (FPCostModel, FPCostModel)>> {
+    let mut params: BTreeMap<CostType, (FPCostModel, FPCostModel)> = BTreeMap::new();
 
     call_bench::<B, ComputeEd25519PubKeyMeasure>(&mut params)?;
     call_bench::<B, ComputeSha256HashMeasure>(&mut params)?;
     call_bench::<B, VerifyEd25519SigMeasure>(&mut params)?;
+    call_bench::<B, ComputeSecp256k1PubKeyMeasure>(&mut params)?;
+    call_bench::<B, VerifyEcdsaSecp256k1SigMeasure>(&mut params)?;
+    call_bench::<B, RecoverEcdsaSecp256k1KeyMeasure>(&mut params)?;
+    call_bench::<B, ComputeSecp256r1PubKeyMeasure>(&mut params)?;
+    call_bench::<B, VerifyEcdsaSecp256r1SigMeasure>(&mut params)?;
+    call_bench::<B, ComputeKeccak256HashMeasure>(&mut params)?;
+    call_bench::<B, ComputeSha512HashMeasure>(&mut params)?;
+    call_bench::<B, ComputeBlake2bHashMeasure>(&mut params)?;
     call_bench::<B, VmInstantiationMeasure>(&mut params)?;
     call_bench::<B, VmMemReadMeasure>(&mut params)?;
     call_bench::<B, VmMemWriteMeasure>(&mut params)?;
@@ -83,9 +91,13 @@ pub(crate) fn for_each_host_cost_measurement<B: Benchmark>(
     call_bench::<B, ChargeBudgetMeasure>(&mut params)?;
     call_bench::<B, HostMemAllocMeasure>(&mut params)?;
     call_bench::<B, HostMemCpyMeasure>(&mut params)?;
+    call_bench::<B, Int256AddSubMeasure>(&mut params)?;
+    call_bench::<B, Int256MulMeasure>(&mut params)?;
+    call_bench::<B, Int256DivMeasure>(&mut params)?;
+    call_bench::<B, Int256ShiftMeasure>(&mut params)?;
 
     if get_explicit_bench_names().is_none() {
-        for cost in ContractCostType::variants() {
+        for cost in CostType::variants() {
             if !params.contains_key(&cost) {
                 eprintln!("warning: missing cost measurement for {:?}", cost);
             }
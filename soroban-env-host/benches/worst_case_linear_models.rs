@@ -1,10 +1,10 @@
 // Run this with
 // $ cargo bench --features vm,testutils --bench worst_case_linear_models -- --nocapture
-// You can optionally pass in args listing the {`ContractCostType`, `WasmInsnType`} combination to run with, e.g.
+// You can optionally pass in args listing the {`CostType`, `WasmInsnType`} combination to run with, e.g.
 // $ cargo bench --features vm,testutils --bench worst_case_linear_models -- VecNew I64Rotr --nocapture
 mod common;
 use common::*;
-use soroban_env_host::{cost_runner::WasmInsnType, xdr::ContractCostType};
+use soroban_env_host::{budget::CostType, cost_runner::WasmInsnType};
 use std::{collections::BTreeMap, fmt::Debug, io::Write};
 use tabwriter::{Alignment, TabWriter};
 
@@ -39,7 +39,17 @@ fn write_cost_params_table<T: Debug>(
     tw.flush()
 }
 
-fn write_budget_params_code(params: BTreeMap<ContractCostType, (FPCostModel, FPCostModel)>) {
+fn cost_type_path(ty: &CostType) -> String {
+    match ty {
+        CostType::Contract(ct) => format!("ContractCostType::{:?}", ct),
+        CostType::Host(ht) => format!("HostCostType::{:?}", ht),
+    }
+}
+
+// Prints the match arms setting the models in `BudgetImpl::default` (for the
+// `ContractCostType`s) and `BudgetImpl::init_host_models` (for the
+// `HostCostType`s).
+fn write_budget_params_code(params: BTreeMap<CostType, (FPCostModel, FPCostModel)>) {
     println!("");
     println!("");
     for (ty, (cpu, _)) in params
@@ -47,8 +57,10 @@ fn write_budget_params_code(params: BTreeMap<ContractCostType, (FPCostModel, FPC
         .map(|(ty, (cpu, mem))| (ty, (cpu.params_as_u64(), mem.params_as_u64())))
     {
         println!(
-            "CostType::{:?} => {{ cpu.const_param = {}; cpu.lin_param = {}; }}",
-            ty, cpu.0, cpu.1
+            "{} => {{ cpu.const_term = {}; cpu.linear_term = {}; }}",
+            cost_type_path(ty),
+            cpu.0,
+            cpu.1
         );
     }
     println!("");
@@ -58,8 +70,10 @@ fn write_budget_params_code(params: BTreeMap<ContractCostType, (FPCostModel, FPC
         .map(|(ty, (cpu, mem))| (ty, (cpu.params_as_u64(), mem.params_as_u64())))
     {
         println!(
-            "CostType::{:?} => {{ mem.const_param = {}; mem.lin_param = {}; }}",
-            ty, mem.0, mem.1
+            "{} => {{ mem.const_term = {}; mem.linear_term = {}; }}",
+            cost_type_path(ty),
+            mem.0,
+            mem.1
         );
     }
 }
@@ -73,7 +87,7 @@ fn main() -> std::io::Result<()> {
     let mut tw = TabWriter::new(vec![])
         .padding(5)
         .alignment(Alignment::Right);
-    write_cost_params_table::<ContractCostType>(&mut tw, &params)?;
+    write_cost_params_table::<CostType>(&mut tw, &params)?;
     write_cost_params_table::<WasmInsnType>(&mut tw, &params_wasm)?;
     eprintln!("{}", String::from_utf8(tw.into_inner().unwrap()).unwrap());
 
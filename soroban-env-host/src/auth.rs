@@ -16,7 +16,7 @@ use crate::native_contract::account_contract::{
 use crate::{Host, HostError};
 
 use super::xdr;
-use super::xdr::{Hash, ScUnknownErrorCode, ScVec};
+use super::xdr::{Hash, ScBytes, ScUnknownErrorCode, ScVec};
 
 // Authorization manager encapsulates host-based authentication & authorization
 // framework.
@@ -33,6 +33,9 @@ pub(crate) struct AuthorizationManager {
     // Every tracker takes care about a single rooted invocation tree for some
     // address. There can be multiple trackers per address.
     trackers: Vec<AuthorizationTracker>,
+    // Trackers of the invocations that contracts have authorized on their own
+    // behalf via `add_invoker_contract_auth`.
+    invoker_contract_trackers: Vec<InvokerContractAuthorizationTracker>,
     // Current call stack consisting only of the contract invocations (i.e. not
     // the host functions).
     call_stack: Vec<ContractInvocation>,
@@ -48,6 +51,38 @@ pub struct RecordedAuthPayload {
     pub invocation: xdr::AuthorizedInvocation,
 }
 
+// Authorization entry that is only valid up to (and including) the
+// `signature_expiration_ledger`.
+// Unlike the regular entries, its nonce doesn't need to match the next value
+// of a per-address counter: any nonce can be used, as long as it hasn't been
+// used by the same address for the same root contract while the signature is
+// valid. This allows signing several independent payloads concurrently.
+// The signature has to be made for the SHA-256 hash of the XDR-serialized
+// `ScVal::Vec` of the `HashIdPreimage::ContractAuth` hash (as `ScVal::Bytes`)
+// and the `signature_expiration_ledger` (as `ScVal::U32`).
+// See `expiring_signature_payload_preimage`.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct ExpiringContractAuth {
+    pub contract_auth: ContractAuth,
+    pub signature_expiration_ledger: u32,
+}
+
+// Builds the value whose XDR hash has to be signed for an
+// `ExpiringContractAuth`, given the hash of its
+// `HashIdPreimage::ContractAuth`.
+pub fn expiring_signature_payload_preimage(
+    contract_auth_payload: [u8; 32],
+    signature_expiration_ledger: u32,
+) -> Result<ScVal, HostError> {
+    Ok(ScVal::Vec(Some(ScVec(
+        vec![
+            ScVal::Bytes(ScBytes(contract_auth_payload.to_vec().try_into()?)),
+            ScVal::U32(signature_expiration_ledger),
+        ]
+        .try_into()?,
+    ))))
+}
+
 // Snapshot of `AuthorizationManager` to use when performing the callstack
 // rollbacks.
 #[derive(Clone)]
@@ -56,6 +91,7 @@ pub struct AuthorizationManagerSnapshot {
     // rollback everything. If this is an issue, then the AuthorizationTracker should
     // probably be separated into 'mutable' and 'immutable' parts.
     trackers: Vec<AuthorizationTracker>,
+    invoker_contract_trackers: Vec<InvokerContractAuthorizationTracker>,
     tracker_by_address_handle: Option<HashMap<u32, usize>>,
 }
 
@@ -111,8 +147,21 @@ struct AuthorizationTracker {
     // Indicates whether nonce still needs to be verified and consumed.
     need_nonce: bool,
     // The value of nonce authorized by the address. Must match the stored
-    // nonce value.
+    // nonce value, unless the signature has an expiration ledger.
     nonce: Option<u64>,
+    // The last ledger the signature is valid for. When set, the nonce only
+    // needs to be unique instead of being sequential.
+    signature_expiration_ledger: Option<u32>,
+}
+
+// Tracks an invocation tree that a contract has authorized on its own behalf
+// for the calls made (directly or not) from its current frame.
+#[derive(Clone)]
+struct InvokerContractAuthorizationTracker {
+    tracker: AuthorizationTracker,
+    // Length of the call stack when the authorization has been added. The
+    // authorization is dropped as soon as the authorizing frame is popped.
+    call_stack_len: usize,
 }
 
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -137,6 +186,21 @@ pub(crate) struct AuthorizedInvocation {
 }
 
 impl AuthorizedInvocation {
+    pub(crate) fn new(
+        contract_id: Hash,
+        function_name: ScSymbol,
+        args: ScVec,
+        sub_invocations: Vec<AuthorizedInvocation>,
+    ) -> Self {
+        Self {
+            contract_id,
+            function_name,
+            args,
+            sub_invocations,
+            is_exhausted: false,
+        }
+    }
+
     fn from_xdr(xdr_invocation: xdr::AuthorizedInvocation) -> Result<Self, HostError> {
         let sub_invocations_xdr = xdr_invocation.sub_invocations.into_vec();
         let sub_invocations = sub_invocations_xdr
@@ -233,11 +297,26 @@ impl AuthorizationManager {
     pub(crate) fn new_enforcing(
         host: &Host,
         auth_entries: Vec<ContractAuth>,
+    ) -> Result<Self, HostError> {
+        Self::new_enforcing_with_expiration(
+            host,
+            auth_entries.into_iter().map(|e| (e, None)).collect(),
+        )
+    }
+
+    // Creates a new enforcing `AuthorizationManager` from the given
+    // authorization entries that may have an expiration ledger.
+    // This should be created once per top-level contract invocation.
+    pub(crate) fn new_enforcing_with_expiration(
+        host: &Host,
+        auth_entries: Vec<(ContractAuth, Option<u32>)>,
     ) -> Result<Self, HostError> {
         let mut trackers = vec![];
-        for auth_entry in auth_entries {
+        for (auth_entry, signature_expiration_ledger) in auth_entries {
             trackers.push(AuthorizationTracker::from_authorization_entry(
-                host, auth_entry,
+                host,
+                auth_entry,
+                signature_expiration_ledger,
             )?);
         }
         Ok(Self {
@@ -245,6 +324,7 @@ impl AuthorizationManager {
             call_stack: vec![],
             budget: host.budget_cloned(),
             trackers,
+            invoker_contract_trackers: vec![],
         })
     }
 
@@ -257,6 +337,7 @@ impl AuthorizationManager {
             call_stack: vec![],
             budget,
             trackers: vec![],
+            invoker_contract_trackers: vec![],
         }
     }
 
@@ -271,6 +352,7 @@ impl AuthorizationManager {
             call_stack: vec![],
             budget,
             trackers: vec![],
+            invoker_contract_trackers: vec![],
         }
     }
 
@@ -291,15 +373,31 @@ impl AuthorizationManager {
         args: ScVec,
     ) -> Result<(), HostError> {
         if let ScAddress::Contract(contract_addr) = &address {
-            // For now we give a blanket approval of the invoker contract to any
-            // calls it made, but never to the deeper calls. It's possible
-            // to eventually add a capability to pre-authorize arbitrary call
-            // stacks on behalf of the contract.
+            // The invoker contract has a blanket approval for any calls it
+            // made directly, so these don't consume its explicit
+            // authorizations.
             if let Ok(invoker_contract) = host.get_invoking_contract_internal() {
                 if &invoker_contract == contract_addr {
                     return Ok(());
                 }
             }
+            // Deeper calls are only authorized on behalf of a contract when
+            // the contract has explicitly authorized them from one of the
+            // frames currently on the stack.
+            if let Some(curr_invocation) = self.call_stack.last() {
+                for invoker_tracker in &mut self.invoker_contract_trackers {
+                    if invoker_tracker.tracker.address.as_ref() == Some(&address)
+                        && invoker_tracker.tracker.maybe_authorize_invocation(
+                            host,
+                            &curr_invocation.contract_id,
+                            &curr_invocation.function_name,
+                            &args,
+                        )?
+                    {
+                        return Ok(());
+                    }
+                }
+            }
         }
 
         if let Some(curr_invocation) = self.call_stack.last() {
@@ -394,6 +492,34 @@ impl AuthorizationManager {
         }
     }
 
+    // Authorizes the provided invocation trees on behalf of the contract that
+    // is currently running. The authorizations can be consumed by
+    // `require_auth` calls for the contract address at any depth of the call
+    // stack until the current frame is popped.
+    pub(crate) fn add_invoker_contract_auth(
+        &mut self,
+        host: &Host,
+        auth_entries: Vec<AuthorizedInvocation>,
+    ) -> Result<(), HostError> {
+        let Some(curr_invocation) = self.call_stack.last() else {
+            return Err(host.err_general("unexpected empty stack in invoker contract auth"))
+        };
+        for root_invocation in auth_entries {
+            self.invoker_contract_trackers
+                .push(InvokerContractAuthorizationTracker {
+                    tracker: AuthorizationTracker::new_invoker_contract_auth(
+                        ScAddress::Contract(
+                            curr_invocation.contract_id.metered_clone(&self.budget)?,
+                        ),
+                        root_invocation,
+                        self.call_stack.len(),
+                    ),
+                    call_stack_len: self.call_stack.len(),
+                });
+        }
+        Ok(())
+    }
+
     // Returns a snapshot of `AuthorizationManager` to use for rollback.
     pub(crate) fn snapshot(&self) -> AuthorizationManagerSnapshot {
         let tracker_by_address_handle = match &self.mode {
@@ -404,6 +530,7 @@ impl AuthorizationManager {
         };
         AuthorizationManagerSnapshot {
             trackers: self.trackers.clone(),
+            invoker_contract_trackers: self.invoker_contract_trackers.clone(),
             tracker_by_address_handle,
         }
     }
@@ -411,6 +538,7 @@ impl AuthorizationManager {
     // Rolls back this `AuthorizationManager` to the snapshot state.
     pub(crate) fn rollback(&mut self, snapshot: AuthorizationManagerSnapshot) {
         self.trackers = snapshot.trackers;
+        self.invoker_contract_trackers = snapshot.invoker_contract_trackers;
         if let Some(tracker_by_address_handle) = snapshot.tracker_by_address_handle {
             match &mut self.mode {
                 AuthorizationMode::Recording(recording_info) => {
@@ -447,6 +575,9 @@ impl AuthorizationManager {
         for tracker in &mut self.trackers {
             tracker.push_frame();
         }
+        for invoker_tracker in &mut self.invoker_contract_trackers {
+            invoker_tracker.tracker.push_frame();
+        }
         Ok(())
     }
 
@@ -465,6 +596,14 @@ impl AuthorizationManager {
         for tracker in &mut self.trackers {
             tracker.pop_frame();
         }
+        // Drop the authorizations made by the contract that is no longer on
+        // the stack.
+        let call_stack_len = self.call_stack.len();
+        self.invoker_contract_trackers
+            .retain(|t| t.call_stack_len <= call_stack_len);
+        for invoker_tracker in &mut self.invoker_contract_trackers {
+            invoker_tracker.tracker.pop_frame();
+        }
     }
 
     // Returns the recorded per-address authorization payloads that would cover the
@@ -592,7 +731,11 @@ impl AuthorizationManager {
 }
 
 impl AuthorizationTracker {
-    fn from_authorization_entry(host: &Host, auth_entry: ContractAuth) -> Result<Self, HostError> {
+    fn from_authorization_entry(
+        host: &Host,
+        auth_entry: ContractAuth,
+        signature_expiration_ledger: Option<u32>,
+    ) -> Result<Self, HostError> {
         let is_invoker = auth_entry.address_with_nonce.is_none();
         let (address, nonce) = if let Some(address_with_nonce) = auth_entry.address_with_nonce {
             (
@@ -610,6 +753,7 @@ impl AuthorizationTracker {
             need_nonce: !is_invoker,
             is_invoker,
             nonce,
+            signature_expiration_ledger,
             invocation_id_in_call_stack: vec![],
             is_valid: true,
         })
@@ -660,9 +804,32 @@ impl AuthorizationTracker {
             need_nonce: false,
             is_invoker,
             nonce,
+            signature_expiration_ledger: None,
         })
     }
 
+    // Creates a tracker for the invocation tree authorized by a contract for
+    // the calls made from its current frame. The contract itself is running,
+    // so it needs neither authentication nor nonce.
+    fn new_invoker_contract_auth(
+        contract_address: ScAddress,
+        root_invocation: AuthorizedInvocation,
+        current_stack_len: usize,
+    ) -> Self {
+        Self {
+            address: Some(contract_address),
+            root_authorized_invocation: root_invocation,
+            invocation_id_in_call_stack: vec![None; current_stack_len],
+            signature_args: Default::default(),
+            is_valid: true,
+            authenticated: true,
+            need_nonce: false,
+            is_invoker: false,
+            nonce: None,
+            signature_expiration_ledger: None,
+        }
+    }
+
     // Tries to find and enforce the provided invocation with this tracker and
     // lazily performs authentication when needed.
     // This is needed for the enforcing mode only.
@@ -696,7 +863,8 @@ impl AuthorizationTracker {
         }
         if !self.authenticated {
             let authenticate_res = self
-                .authenticate(host)
+                .check_signature_expiration(host)
+                .and_then(|_| self.authenticate(host))
                 .and_then(|_| self.verify_nonce(host));
             if let Some(err) = authenticate_res.err() {
                 self.is_valid = false;
@@ -783,10 +951,21 @@ impl AuthorizationTracker {
         }
         self.need_nonce = false;
         if let Some(addr) = &self.address {
-            Ok(Some(host.read_and_consume_nonce(
-                &self.root_authorized_invocation.contract_id,
-                addr,
-            )?))
+            match (self.signature_expiration_ledger, self.nonce) {
+                (Some(expiration_ledger), Some(nonce)) => {
+                    host.consume_unique_nonce(
+                        &self.root_authorized_invocation.contract_id,
+                        addr,
+                        nonce,
+                        expiration_ledger,
+                    )?;
+                    Ok(Some(nonce))
+                }
+                _ => Ok(Some(host.read_and_consume_nonce(
+                    &self.root_authorized_invocation.contract_id,
+                    addr,
+                )?)),
+            }
         } else {
             Ok(None)
         }
@@ -865,6 +1044,34 @@ impl AuthorizationTracker {
         }
     }
 
+    // Makes sure that the signature hasn't expired yet and that it doesn't
+    // outlive the maximum entry expiration, as its nonce has to be stored
+    // until the signature expires.
+    fn check_signature_expiration(&self, host: &Host) -> Result<(), HostError> {
+        let Some(expiration_ledger) = self.signature_expiration_ledger else {
+            return Ok(())
+        };
+        let (ledger_seq, max_entry_expiration) =
+            host.with_ledger_info(|li| Ok((li.sequence_number, li.max_entry_expiration)))?;
+        if expiration_ledger < ledger_seq {
+            return Err(host.err_status_msg_with_args(
+                ScHostAuthErrorCode::NotAuthorized,
+                "signature has expired",
+                &[expiration_ledger.into(), ledger_seq.into()],
+            ));
+        }
+        if max_entry_expiration != 0
+            && expiration_ledger > ledger_seq.saturating_add(max_entry_expiration)
+        {
+            return Err(host.err_status_msg_with_args(
+                ScHostAuthErrorCode::NotAuthorized,
+                "signature expiration ledger is too far in the future",
+                &[expiration_ledger.into(), ledger_seq.into()],
+            ));
+        }
+        Ok(())
+    }
+
     // Computes the payload that has to be signed in order to authenticate
     // the authorized invocation tree corresponding to this tracker.
     fn get_signature_payload(&self, host: &Host) -> Result<[u8; 32], HostError> {
@@ -879,7 +1086,14 @@ impl AuthorizationTracker {
                 .ok_or_else(|| host.err_general("unexpected missing nonce"))?,
         });
 
-        host.metered_hash_xdr(&payload_preimage)
+        let payload = host.metered_hash_xdr(&payload_preimage)?;
+        match self.signature_expiration_ledger {
+            None => Ok(payload),
+            Some(expiration_ledger) => host.metered_hash_xdr(&expiring_signature_payload_preimage(
+                payload,
+                expiration_ledger,
+            )?),
+        }
     }
 
     fn authenticate(&self, host: &Host) -> Result<(), HostError> {
@@ -966,6 +1180,56 @@ impl Host {
         Ok(curr_nonce)
     }
 
+    // Marks the `nonce` as used by `address` for the signatures made for
+    // `contract_id`. The nonce is stored in a contract data entry that
+    // expires together with the signature, so it can't be reused while the
+    // signature is valid.
+    fn consume_unique_nonce(
+        &self,
+        contract_id: &Hash,
+        address: &ScAddress,
+        nonce: u64,
+        expiration_ledger: u32,
+    ) -> Result<(), HostError> {
+        // Contracts can't build `LedgerKeyNonce` values, so this key can't
+        // collide with the regular contract data.
+        let nonce_key_scval = ScVal::Vec(Some(ScVec(
+            vec![
+                ScVal::LedgerKeyNonce(ScNonceKey {
+                    nonce_address: address.metered_clone(self.budget_ref())?,
+                }),
+                ScVal::U64(nonce),
+            ]
+            .try_into()?,
+        )));
+        let nonce_key = self.storage_key_for_contract(
+            contract_id.metered_clone(self.budget_ref())?,
+            nonce_key_scval.metered_clone(self.budget_ref())?,
+        );
+        if self.with_mut_storage(|storage| storage.has(&nonce_key, self.budget_ref()))?
+            && !self.is_entry_expired(&nonce_key)?
+        {
+            return Err(self.err_status_msg(
+                ScHostAuthErrorCode::NonceError,
+                "nonce has already been used",
+            ));
+        }
+        let data = LedgerEntryData::ContractData(ContractDataEntry {
+            contract_id: contract_id.metered_clone(self.budget_ref())?,
+            key: nonce_key_scval,
+            val: ScVal::Void,
+        });
+        let entry = LedgerEntry {
+            last_modified_ledger_seq: 0,
+            data,
+            ext: LedgerEntryExt::V0,
+        };
+        self.with_mut_storage(|storage| {
+            storage.put(&nonce_key, &Rc::new(entry), self.budget_ref())?;
+            storage.set_expiration(&nonce_key, expiration_ledger, self.budget_ref())
+        })
+    }
+
     fn read_and_consume_nonce(
         &self,
         contract_id: &Hash,
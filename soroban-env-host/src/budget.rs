@@ -5,9 +5,10 @@ use std::{
 };
 
 use crate::{
+    fees::TransactionResources,
     xdr::{
-        ContractCostParamEntry, ContractCostParams, ContractCostType, ExtensionPoint,
-        ScUnknownErrorCode, ScVmErrorCode,
+        ContractCostParamEntry, ContractCostParams, ContractCostType, ExtensionPoint, Hash,
+        ScHostStorageErrorCode, ScUnknownErrorCode, ScVmErrorCode,
     },
     Host, HostError,
 };
@@ -67,14 +68,130 @@ impl HostCostModel for ContractCostParamEntry {
     }
 }
 
+/// Host operations that are metered under cost types of their own, as none
+/// of the [ContractCostType]s of the XDR describes them.
+///
+/// The XDR has no [ContractCostType] for them, so their models are
+/// configured by the entries of the network's [ContractCostParams] that
+/// follow the ones of the [ContractCostType]s, in the order of
+/// [HostCostType::variants] (that is, at their [CostType::index]). When the
+/// configuration doesn't have these entries, [Budget::from_configs] gives
+/// them the same default parameters as [Budget::default] does.
+/// They are charged, tracked and reported like the [ContractCostType]s,
+/// through the [CostType] that wraps both.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HostCostType {
+    /// Addition, subtraction or comparison of two integers of up to 256
+    /// bits.
+    Int256AddSub = 0,
+    /// Multiplication of two integers of up to 256 bits.
+    Int256Mul = 1,
+    /// Division or remainder of two integers of up to 256 bits.
+    Int256Div = 2,
+    /// Shift of an integer of up to 256 bits.
+    Int256Shift = 3,
+    /// Decoding of a secp256k1 public key from its SEC1 encoding, which
+    /// involves a square root for the compressed one.
+    ComputeSecp256k1PubKey = 4,
+    /// Verification of an ECDSA secp256k1 signature of a 32-byte digest.
+    VerifyEcdsaSecp256k1Sig = 5,
+    /// Recovery of the secp256k1 public key out of an ECDSA signature of a
+    /// 32-byte digest.
+    RecoverEcdsaSecp256k1Key = 6,
+    /// Decoding of a secp256r1 public key from its SEC1 encoding.
+    ComputeSecp256r1PubKey = 7,
+    /// Verification of an ECDSA secp256r1 signature of a 32-byte digest.
+    VerifyEcdsaSecp256r1Sig = 8,
+    /// Keccak256 hash of a byte buffer, linear in its length.
+    ComputeKeccak256Hash = 9,
+    /// Sha512 hash of a byte buffer, linear in its length.
+    ComputeSha512Hash = 10,
+    /// Blake2b hash, with a 32-byte output, of a byte buffer, linear in its
+    /// length.
+    ComputeBlake2bHash = 11,
+}
+
+impl HostCostType {
+    pub const fn variants() -> [HostCostType; 12] {
+        [
+            HostCostType::Int256AddSub,
+            HostCostType::Int256Mul,
+            HostCostType::Int256Div,
+            HostCostType::Int256Shift,
+            HostCostType::ComputeSecp256k1PubKey,
+            HostCostType::VerifyEcdsaSecp256k1Sig,
+            HostCostType::RecoverEcdsaSecp256k1Key,
+            HostCostType::ComputeSecp256r1PubKey,
+            HostCostType::VerifyEcdsaSecp256r1Sig,
+            HostCostType::ComputeKeccak256Hash,
+            HostCostType::ComputeSha512Hash,
+            HostCostType::ComputeBlake2bHash,
+        ]
+    }
+}
+
+/// The type of a charge to the [Budget]: either one of the
+/// [ContractCostType]s of the XDR or one of the [HostCostType]s.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CostType {
+    Contract(ContractCostType),
+    Host(HostCostType),
+}
+
+impl CostType {
+    /// All the cost types, the [ContractCostType]s first.
+    pub fn variants() -> impl Iterator<Item = CostType> {
+        ContractCostType::variants()
+            .into_iter()
+            .map(CostType::Contract)
+            .chain(HostCostType::variants().into_iter().map(CostType::Host))
+    }
+
+    pub fn count() -> usize {
+        ContractCostType::variants().len() + HostCostType::variants().len()
+    }
+
+    /// Index of the cost type in the per-type vectors of the budget, which
+    /// hold the [HostCostType]s after the [ContractCostType]s.
+    pub fn index(self) -> usize {
+        match self {
+            CostType::Contract(ct) => ct as usize,
+            CostType::Host(ht) => ContractCostType::variants().len() + ht as usize,
+        }
+    }
+}
+
+impl From<ContractCostType> for CostType {
+    fn from(ct: ContractCostType) -> Self {
+        CostType::Contract(ct)
+    }
+}
+
+impl From<HostCostType> for CostType {
+    fn from(ht: HostCostType) -> Self {
+        CostType::Host(ht)
+    }
+}
+
+// Prints the name of the wrapped type only, which is how the cost types are
+// named in the reports and picked in the benchmarks.
+impl Debug for CostType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CostType::Contract(ct) => write!(f, "{:?}", ct),
+            CostType::Host(ht) => write!(f, "{:?}", ht),
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct BudgetDimension {
     trapcode: ScVmErrorCode,
 
     /// A set of cost models that map input values (eg. event counts, object
     /// sizes) from some CostType to whatever concrete resource type is being
-    /// tracked by this dimension (eg. cpu or memory). CostType enum values are
-    /// used as indexes into this vector, to make runtime lookups as cheap as
+    /// tracked by this dimension (eg. cpu or memory). [CostType::index] is
+    /// used to index into this vector, to make runtime lookups as cheap as
     /// possible.
     cost_models: Vec<ContractCostParamEntry>,
 
@@ -98,9 +215,9 @@ impl Debug for BudgetDimension {
             self.limit, self.total_count
         )?;
 
-        for ct in ContractCostType::variants() {
-            writeln!(f, "CostType {:?}, count {}", ct, self.counts[ct as usize])?;
-            writeln!(f, "model: {:?}", self.cost_models[ct as usize])?;
+        for ct in CostType::variants() {
+            writeln!(f, "CostType {:?}, count {}", ct, self.counts[ct.index()])?;
+            writeln!(f, "model: {:?}", self.cost_models[ct.index()])?;
         }
         Ok(())
     }
@@ -115,7 +232,7 @@ impl BudgetDimension {
             counts: Default::default(),
             total_count: Default::default(),
         };
-        for _ct in ContractCostType::variants() {
+        for _ct in CostType::variants() {
             bd.cost_models.push(ContractCostParamEntry {
                 const_term: 0,
                 linear_term: 0,
@@ -126,26 +243,51 @@ impl BudgetDimension {
         bd
     }
 
+    /// Takes the models of the [ContractCostType]s from `cost_params`. The
+    /// models of the [HostCostType]s are left at zero, see
+    /// [BudgetDimension::set_host_models].
     pub fn from_config(trapcode: ScVmErrorCode, cost_params: ContractCostParams) -> Self {
+        let zero = ContractCostParamEntry {
+            const_term: 0,
+            linear_term: 0,
+            ext: ExtensionPoint::V0,
+        };
+        let mut cost_models = cost_params.0.to_vec();
+        cost_models.resize(ContractCostType::variants().len(), zero.clone());
+        cost_models.resize(CostType::count(), zero);
         Self {
             trapcode,
-            cost_models: cost_params.0.to_vec(),
+            cost_models,
             limit: Default::default(),
-            counts: vec![0; cost_params.0.len()],
+            counts: vec![0; CostType::count()],
             total_count: Default::default(),
         }
     }
 
-    pub fn get_cost_model(&self, ty: ContractCostType) -> &ContractCostParamEntry {
-        &self.cost_models[ty as usize]
+    /// Sets the models of the [HostCostType]s from the entries of
+    /// `cost_params` that follow the ones of the [ContractCostType]s. The
+    /// models of the [HostCostType]s with no entry are left unchanged.
+    pub fn set_host_models(&mut self, cost_params: &ContractCostParams) {
+        for (ht, model) in HostCostType::variants().into_iter().zip(
+            cost_params
+                .0
+                .iter()
+                .skip(ContractCostType::variants().len()),
+        ) {
+            *self.get_cost_model_mut(ht) = model.clone();
+        }
     }
 
-    pub fn get_cost_model_mut(&mut self, ty: ContractCostType) -> &mut ContractCostParamEntry {
-        &mut self.cost_models[ty as usize]
+    pub fn get_cost_model(&self, ty: impl Into<CostType>) -> &ContractCostParamEntry {
+        &self.cost_models[ty.into().index()]
     }
 
-    pub fn get_count(&self, ty: ContractCostType) -> u64 {
-        self.counts[ty as usize]
+    pub fn get_cost_model_mut(&mut self, ty: impl Into<CostType>) -> &mut ContractCostParamEntry {
+        &mut self.cost_models[ty.into().index()]
+    }
+
+    pub fn get_count(&self, ty: impl Into<CostType>) -> u64 {
+        self.counts[ty.into().index()]
     }
 
     pub fn get_total_count(&self) -> u64 {
@@ -175,13 +317,13 @@ impl BudgetDimension {
     /// model, and amount charged is iterations * const_param.
     pub fn charge(
         &mut self,
-        ty: ContractCostType,
+        ty: CostType,
         iterations: u64,
         input: Option<u64>,
     ) -> Result<(), HostError> {
         let cm = self.get_cost_model(ty);
         let amount = cm.evaluate(input)?.saturating_mul(iterations);
-        self.counts[ty as usize] = self.counts[ty as usize].saturating_add(amount);
+        self.counts[ty.index()] = self.counts[ty.index()].saturating_add(amount);
         self.total_count = self.total_count.saturating_add(amount);
         if self.is_over_budget() {
             // TODO: convert this to a proper error code type.
@@ -200,14 +342,162 @@ impl BudgetDimension {
     }
 }
 
+/// The ledger IO resources metered by the [Budget] alongside CPU and memory.
+///
+/// Unlike the [ContractCostType]s, these are not subject to a cost model: they
+/// are charged directly in their natural units (entries or bytes) and each of
+/// them is compared to its own limit. They correspond to the ledger-related
+/// fields of [TransactionResources].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LedgerIoType {
+    /// Number of distinct ledger entries accessed, including the ones that
+    /// are also written.
+    ReadEntries = 0,
+    /// Number of distinct ledger entries written or deleted.
+    WriteEntries = 1,
+    /// Size of the XDR of the accessed ledger entries, as they were before
+    /// the first access.
+    ReadBytes = 2,
+    /// Size of the XDR of the written ledger entries.
+    WriteBytes = 3,
+    /// Size of the transaction metadata: the XDR of the contract events and
+    /// the XDR of the written entries both before and after the write.
+    MetadataBytes = 4,
+}
+
+impl LedgerIoType {
+    pub const fn variants() -> [LedgerIoType; 5] {
+        [
+            LedgerIoType::ReadEntries,
+            LedgerIoType::WriteEntries,
+            LedgerIoType::ReadBytes,
+            LedgerIoType::WriteBytes,
+            LedgerIoType::MetadataBytes,
+        ]
+    }
+}
+
+/// The error code of exceeding a [LedgerIoType] limit. The XDR doesn't have
+/// a dedicated code for the ledger IO limits, so they fail with the generic
+/// storage error, which the host doesn't use for anything else. The
+/// exceeded [LedgerIoType] is available from [HostError::ledger_io_exceeded].
+pub const LEDGER_IO_LIMIT_EXCEEDED: ScHostStorageErrorCode = ScHostStorageErrorCode::UnknownError;
+
+/// Counts and limits of the [LedgerIoType] resources. All the limits are
+/// unbounded by default.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LedgerIoDimension {
+    counts: [u64; 5],
+    limits: [u64; 5],
+}
+
+impl Default for LedgerIoDimension {
+    fn default() -> Self {
+        Self {
+            counts: [0; 5],
+            limits: [u64::MAX; 5],
+        }
+    }
+}
+
+impl LedgerIoDimension {
+    pub fn get_count(&self, ty: LedgerIoType) -> u64 {
+        self.counts[ty as usize]
+    }
+
+    pub fn get_limit(&self, ty: LedgerIoType) -> u64 {
+        self.limits[ty as usize]
+    }
+
+    pub fn reset(&mut self, limits: [u64; 5]) {
+        self.limits = limits;
+        self.counts = [0; 5];
+    }
+
+    pub fn is_over_budget(&self, ty: LedgerIoType) -> bool {
+        self.counts[ty as usize] > self.limits[ty as usize]
+    }
+
+    pub fn reset_counts(&mut self) {
+        self.counts = [0; 5];
+    }
+
+    /// Charges `amount` units of `ty`. Going over the limit fails with
+    /// [LEDGER_IO_LIMIT_EXCEEDED], and the error records the exceeded
+    /// [LedgerIoType] (see [HostError::ledger_io_exceeded]).
+    pub fn charge(&mut self, ty: LedgerIoType, amount: u64) -> Result<(), HostError> {
+        self.counts[ty as usize] = self.counts[ty as usize].saturating_add(amount);
+        if self.is_over_budget(ty) {
+            let mut err: HostError = LEDGER_IO_LIMIT_EXCEEDED.into();
+            err.ledger_io_exceeded = Some(ty);
+            Err(err)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// The frame that was active when the budget got exceeded.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ExceededFrame {
+    /// Id of the running contract, if the frame belongs to one.
+    pub contract_id: Option<Hash>,
+    /// Name of the invoked function (or host function type).
+    pub function: String,
+}
+
+/// Diagnostics recorded at the moment the budget got exceeded, i.e. by the
+/// first charge that went over the CPU or memory limit.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BudgetExceeded {
+    /// Either [ScVmErrorCode::TrapCpuLimitExceeded] or
+    /// [ScVmErrorCode::TrapMemLimitExceeded].
+    pub trapcode: ScVmErrorCode,
+    /// The cost type of the charge that went over the limit.
+    pub cost_type: CostType,
+    pub limit: u64,
+    pub total_count: u64,
+    /// Amount charged to the exceeded dimension so far, indexed by
+    /// [CostType::index].
+    pub counts: Vec<u64>,
+    /// The innermost frame that was active, filled in by the
+    /// [Host](crate::Host) as the error unwinds the frame stack.
+    pub frame: Option<ExceededFrame>,
+}
+
+impl Display for BudgetExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{:?} charging {:?}: limit {}, used {}",
+            self.trapcode, self.cost_type, self.limit, self.total_count
+        )?;
+        if let Some(frame) = &self.frame {
+            match &frame.contract_id {
+                Some(id) => writeln!(f, "in contract {:?}, function {}", id, frame.function)?,
+                None => writeln!(f, "in {}", frame.function)?,
+            }
+        }
+        for ct in CostType::variants() {
+            let count = self.counts[ct.index()];
+            if count != 0 {
+                writeln!(f, "{:<25}{:<15}", format!("{:?}", ct), count)?;
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub(crate) struct BudgetImpl {
     pub cpu_insns: BudgetDimension,
     pub mem_bytes: BudgetDimension,
+    pub ledger_io: LedgerIoDimension,
     /// Tracks the `(sum_of_iterations, total_input)` for each `CostType`, for purposes of
     /// calibration and reporting; not used for budget-limiting per se.
     tracker: Vec<(u64, Option<u64>)>,
     enabled: bool,
+    exceeded: Option<BudgetExceeded>,
 }
 
 impl BudgetImpl {
@@ -221,16 +511,21 @@ impl BudgetImpl {
         let mut b = Self {
             cpu_insns: BudgetDimension::from_config(
                 ScVmErrorCode::TrapCpuLimitExceeded,
-                cpu_cost_params,
+                cpu_cost_params.clone(),
             ),
             mem_bytes: BudgetDimension::from_config(
                 ScVmErrorCode::TrapMemLimitExceeded,
-                mem_cost_params,
+                mem_cost_params.clone(),
             ),
-            tracker: vec![(0, None); ContractCostType::variants().len()],
+            ledger_io: Default::default(),
+            tracker: vec![(0, None); CostType::count()],
             enabled: true,
+            exceeded: None,
         };
 
+        b.init_host_models();
+        b.cpu_insns.set_host_models(&cpu_cost_params);
+        b.mem_bytes.set_host_models(&mem_cost_params);
         b.init_tracker();
 
         b.cpu_insns.reset(cpu_limit);
@@ -238,12 +533,31 @@ impl BudgetImpl {
         b
     }
 
+    fn record_exceeded(&mut self, ty: CostType) {
+        if self.exceeded.is_some() {
+            return;
+        }
+        let dim = if self.cpu_insns.is_over_budget() {
+            &self.cpu_insns
+        } else {
+            &self.mem_bytes
+        };
+        self.exceeded = Some(BudgetExceeded {
+            trapcode: dim.trapcode,
+            cost_type: ty,
+            limit: dim.limit,
+            total_count: dim.total_count,
+            counts: dim.counts.clone(),
+            frame: None,
+        });
+    }
+
     fn init_tracker(&mut self) {
         for ct in ContractCostType::variants() {
             // Define what inputs actually mean. For any constant-cost types -- whether it is a
             // true constant unit cost type, or empirically assigned (via measurement) constant
             // type -- we leave the input as `None`, otherwise, we initialize the input to 0.
-            let i = ct as usize;
+            let i = CostType::from(ct).index();
             match ct {
                 ContractCostType::WasmInsnExec => (),
                 ContractCostType::WasmMemAlloc => self.tracker[i].1 = Some(0), // number of pages in wasm linear memory to allocate (each page is 64kB)
@@ -268,6 +582,108 @@ impl BudgetImpl {
                 ContractCostType::ChargeBudget => (),
             }
         }
+        for ht in HostCostType::variants() {
+            let i = CostType::from(ht).index();
+            match ht {
+                HostCostType::Int256AddSub => (),
+                HostCostType::Int256Mul => (),
+                HostCostType::Int256Div => (),
+                HostCostType::Int256Shift => (),
+                HostCostType::ComputeSecp256k1PubKey => (),
+                HostCostType::VerifyEcdsaSecp256k1Sig => (),
+                HostCostType::RecoverEcdsaSecp256k1Key => (),
+                HostCostType::ComputeSecp256r1PubKey => (),
+                HostCostType::VerifyEcdsaSecp256r1Sig => (),
+                HostCostType::ComputeKeccak256Hash => self.tracker[i].1 = Some(0), // number of bytes in the buffer
+                HostCostType::ComputeSha512Hash => self.tracker[i].1 = Some(0), // number of bytes in the buffer
+                HostCostType::ComputeBlake2bHash => self.tracker[i].1 = Some(0), // number of bytes in the buffer
+            }
+        }
+    }
+
+    /// Sets the default models of the [HostCostType]s, which the network
+    /// configuration can override (see [BudgetDimension::set_host_models]).
+    ///
+    /// These are not taken from calibration results yet: they are eye-balled
+    /// upper bounds of the operations alone, to be replaced with the output of
+    /// the `worst_case_linear_models` benchmark (run with `WRITE_BUDGET_PARAMS`
+    /// set) for their cost types. Converting the operands and the results
+    /// from and to host values is charged separately. The ECDSA ones are
+    /// scaled up from the calibrated ed25519 models, as the short Weierstrass
+    /// curves cost two to three times as much as the Edwards one. The hashes
+    /// are scaled from the calibrated sha256 model by their throughput
+    /// relative to it, and their memory is the hash they return.
+    fn init_host_models(&mut self) {
+        for ht in HostCostType::variants() {
+            let cpu = self.cpu_insns.get_cost_model_mut(ht);
+            match ht {
+                HostCostType::Int256AddSub => {
+                    cpu.const_term = 100;
+                    cpu.linear_term = 0;
+                }
+                HostCostType::Int256Mul => {
+                    cpu.const_term = 400;
+                    cpu.linear_term = 0;
+                }
+                HostCostType::Int256Div => {
+                    cpu.const_term = 2000;
+                    cpu.linear_term = 0;
+                }
+                HostCostType::Int256Shift => {
+                    cpu.const_term = 50;
+                    cpu.linear_term = 0;
+                }
+                HostCostType::ComputeSecp256k1PubKey => {
+                    cpu.const_term = 60000;
+                    cpu.linear_term = 0;
+                }
+                HostCostType::VerifyEcdsaSecp256k1Sig => {
+                    cpu.const_term = 1000000;
+                    cpu.linear_term = 0;
+                }
+                HostCostType::RecoverEcdsaSecp256k1Key => {
+                    cpu.const_term = 1300000;
+                    cpu.linear_term = 0;
+                }
+                HostCostType::ComputeSecp256r1PubKey => {
+                    cpu.const_term = 80000;
+                    cpu.linear_term = 0;
+                }
+                HostCostType::VerifyEcdsaSecp256r1Sig => {
+                    cpu.const_term = 1500000;
+                    cpu.linear_term = 0;
+                }
+                HostCostType::ComputeKeccak256Hash => {
+                    cpu.const_term = 3766;
+                    cpu.linear_term = 47;
+                }
+                HostCostType::ComputeSha512Hash => {
+                    cpu.const_term = 2868;
+                    cpu.linear_term = 24;
+                }
+                HostCostType::ComputeBlake2bHash => {
+                    cpu.const_term = 2390;
+                    cpu.linear_term = 19;
+                }
+            }
+
+            let mem = self.mem_bytes.get_cost_model_mut(ht);
+            match ht {
+                HostCostType::ComputeKeccak256Hash | HostCostType::ComputeBlake2bHash => {
+                    mem.const_term = 40;
+                    mem.linear_term = 0;
+                }
+                HostCostType::ComputeSha512Hash => {
+                    mem.const_term = 72;
+                    mem.linear_term = 0;
+                }
+                // None of the others allocates.
+                _ => {
+                    mem.const_term = 0;
+                    mem.linear_term = 0;
+                }
+            }
+        }
     }
 }
 
@@ -298,8 +714,8 @@ impl Debug for BudgetImpl {
             "const_term_mem",
             "lin_term_mem",
         )?;
-        for ct in ContractCostType::variants() {
-            let i = ct as usize;
+        for ct in CostType::variants() {
+            let i = ct.index();
             writeln!(
                 f,
                 "{:<25}{:<15}{:<15}{:<15}{:<15}{:<20}{:<20}{:<20}{:<20}",
@@ -338,8 +754,8 @@ impl Display for BudgetImpl {
             "{:<25}{:<15}{:<15}",
             "CostType", "cpu_insns", "mem_bytes",
         )?;
-        for ct in ContractCostType::variants() {
-            let i = ct as usize;
+        for ct in CostType::variants() {
+            let i = ct.index();
             writeln!(
                 f,
                 "{:<25}{:<15}{:<15}",
@@ -385,7 +801,9 @@ impl AsBudget for Host {
 }
 
 impl Budget {
-    /// Initializes the budget from network configuration settings.
+    /// Initializes the budget from network configuration settings. The
+    /// entries of the cost params past the [ContractCostType]s configure the
+    /// [HostCostType]s, see [HostCostType].
     pub fn from_configs(
         cpu_limit: u64,
         mem_limit: u64,
@@ -410,7 +828,7 @@ impl Budget {
 
     fn charge_in_bulk(
         &self,
-        ty: ContractCostType,
+        ty: CostType,
         iterations: u64,
         input: Option<u64>,
     ) -> Result<(), HostError> {
@@ -442,10 +860,15 @@ impl Budget {
         // do the actual budget charging
         self.mut_budget(|mut b| {
             // we already know `ChargeBudget` only affects the cpu budget
-            b.cpu_insns
-                .charge(ContractCostType::ChargeBudget, 1, None)?;
-            b.cpu_insns.charge(ty, iterations, input)?;
-            b.mem_bytes.charge(ty, iterations, input)
+            let res = b
+                .cpu_insns
+                .charge(ContractCostType::ChargeBudget.into(), 1, None)
+                .and_then(|_| b.cpu_insns.charge(ty, iterations, input))
+                .and_then(|_| b.mem_bytes.charge(ty, iterations, input));
+            if res.is_err() {
+                b.record_exceeded(ty);
+            }
+            res
         })
     }
 
@@ -454,8 +877,8 @@ impl Budget {
     /// the input. If the input is `None`, the model is assumed to be constant.
     /// Otherwise it is a linear model.  The caller needs to ensure the input
     /// passed is consistent with the inherent model underneath.
-    pub fn charge(&self, ty: ContractCostType, input: Option<u64>) -> Result<(), HostError> {
-        self.charge_in_bulk(ty, 1, input)
+    pub fn charge(&self, ty: impl Into<CostType>, input: Option<u64>) -> Result<(), HostError> {
+        self.charge_in_bulk(ty.into(), 1, input)
     }
 
     /// Performs a bulk charge to the budget under the specified [`CostType`].
@@ -466,11 +889,11 @@ impl Budget {
     /// underneath the [`CostType`] (linear/constant).
     pub fn batched_charge(
         &self,
-        ty: ContractCostType,
+        ty: impl Into<CostType>,
         iterations: u64,
         input: Option<u64>,
     ) -> Result<(), HostError> {
-        self.charge_in_bulk(ty, iterations, input)
+        self.charge_in_bulk(ty.into(), iterations, input)
     }
 
     pub fn with_free_budget<F, T>(&self, f: F) -> Result<T, HostError>
@@ -491,15 +914,33 @@ impl Budget {
         res
     }
 
-    pub fn get_tracker(&self, ty: ContractCostType) -> (u64, Option<u64>) {
-        self.0.borrow().tracker[ty as usize]
+    pub fn get_tracker(&self, ty: impl Into<CostType>) -> (u64, Option<u64>) {
+        self.0.borrow().tracker[ty.into().index()]
     }
 
-    pub(crate) fn get_tracker_mut<F>(&self, ty: ContractCostType, f: F) -> Result<(), HostError>
+    pub(crate) fn get_tracker_mut<F>(&self, ty: impl Into<CostType>, f: F) -> Result<(), HostError>
     where
         F: FnOnce(&mut (u64, Option<u64>)) -> Result<(), HostError>,
     {
-        f(&mut self.0.borrow_mut().tracker[ty as usize])
+        f(&mut self.0.borrow_mut().tracker[ty.into().index()])
+    }
+
+    /// Returns the diagnostics recorded when the budget got exceeded, if it
+    /// did since the last reset.
+    pub fn get_exceeded(&self) -> Option<BudgetExceeded> {
+        self.0.borrow().exceeded.clone()
+    }
+
+    /// Attributes the recorded budget exhaustion to `frame`, unless it has
+    /// already been attributed. Returns `true` if the frame has been set.
+    pub(crate) fn set_exceeded_frame(&self, frame: ExceededFrame) -> bool {
+        match &mut self.0.borrow_mut().exceeded {
+            Some(exceeded) if exceeded.frame.is_none() => {
+                exceeded.frame = Some(frame);
+                true
+            }
+            _ => false,
+        }
     }
 
     pub fn get_cpu_insns_count(&self) -> u64 {
@@ -517,11 +958,13 @@ impl Budget {
     pub fn reset_unlimited(&self) {
         self.reset_unlimited_cpu();
         self.reset_unlimited_mem();
+        self.reset_ledger_io_counts();
     }
 
     pub fn reset_unlimited_cpu(&self) {
         self.mut_budget(|mut b| {
             b.cpu_insns.reset(u64::MAX);
+            b.exceeded = None;
             Ok(())
         })
         .unwrap(); // panic means multiple-mut-borrow bug
@@ -531,6 +974,7 @@ impl Budget {
     pub fn reset_unlimited_mem(&self) {
         self.mut_budget(|mut b| {
             b.mem_bytes.reset(u64::MAX);
+            b.exceeded = None;
             Ok(())
         })
         .unwrap(); // panic means multiple-mut-borrow bug
@@ -548,6 +992,8 @@ impl Budget {
         self.mut_budget(|mut b| {
             b.cpu_insns.reset(cpu);
             b.mem_bytes.reset(mem);
+            b.ledger_io.reset_counts();
+            b.exceeded = None;
             Ok(())
         })
         .unwrap(); // impossible to panic
@@ -555,6 +1001,75 @@ impl Budget {
         self.reset_tracker()
     }
 
+    /// Charges `amount` units of the ledger IO resource `ty`. Unlike
+    /// [Budget::charge], this is not affected by
+    /// [with_free_budget](Budget::with_free_budget): ledger IO is accounted
+    /// for no matter what CPU and memory the host spends on it.
+    pub fn charge_ledger_io(&self, ty: LedgerIoType, amount: u64) -> Result<(), HostError> {
+        self.mut_budget(|mut b| b.ledger_io.charge(ty, amount))
+    }
+
+    pub fn get_ledger_io_count(&self, ty: LedgerIoType) -> u64 {
+        self.0.borrow().ledger_io.get_count(ty)
+    }
+
+    pub fn get_ledger_io_limit(&self, ty: LedgerIoType) -> u64 {
+        self.0.borrow().ledger_io.get_limit(ty)
+    }
+
+    /// Limits the ledger IO to the resources declared by a transaction. Since
+    /// [LedgerIoType::ReadEntries] also covers the written entries, its limit
+    /// is the sum of the declared read and write entries.
+    pub fn reset_ledger_io_limits(&self, resources: &TransactionResources) {
+        self.mut_budget(|mut b| {
+            b.ledger_io.reset([
+                u64::from(resources.read_entries).saturating_add(resources.write_entries.into()),
+                resources.write_entries.into(),
+                resources.read_bytes.into(),
+                resources.write_bytes.into(),
+                resources.metadata_size_bytes.into(),
+            ]);
+            Ok(())
+        })
+        .unwrap(); // impossible to panic
+    }
+
+    /// Resets the ledger IO counts, keeping their limits.
+    pub fn reset_ledger_io_counts(&self) {
+        self.mut_budget(|mut b| {
+            b.ledger_io.reset_counts();
+            Ok(())
+        })
+        .unwrap(); // impossible to panic
+    }
+
+    pub fn reset_unlimited_ledger_io(&self) {
+        self.mut_budget(|mut b| {
+            b.ledger_io.reset([u64::MAX; 5]);
+            Ok(())
+        })
+        .unwrap(); // impossible to panic
+    }
+
+    /// Returns the resources consumed so far, in the form expected by
+    /// [compute_transaction_resource_fee](crate::fees::compute_transaction_resource_fee).
+    /// The transaction size is not known to the host and has to be provided
+    /// by the caller.
+    pub fn get_transaction_resources(&self, transaction_size_bytes: u32) -> TransactionResources {
+        let b = self.0.borrow();
+        let count = |ty| u32::try_from(b.ledger_io.get_count(ty)).unwrap_or(u32::MAX);
+        TransactionResources {
+            instructions: u32::try_from(b.cpu_insns.get_total_count()).unwrap_or(u32::MAX),
+            read_entries: count(LedgerIoType::ReadEntries)
+                .saturating_sub(count(LedgerIoType::WriteEntries)),
+            write_entries: count(LedgerIoType::WriteEntries),
+            read_bytes: count(LedgerIoType::ReadBytes),
+            write_bytes: count(LedgerIoType::WriteBytes),
+            metadata_size_bytes: count(LedgerIoType::MetadataBytes),
+            transaction_size_bytes,
+        }
+    }
+
     #[cfg(test)]
     pub fn reset_models(&self) {
         self.mut_budget(|mut b| {
@@ -573,8 +1088,10 @@ impl Default for BudgetImpl {
         let mut b = Self {
             cpu_insns: BudgetDimension::new(ScVmErrorCode::TrapCpuLimitExceeded),
             mem_bytes: BudgetDimension::new(ScVmErrorCode::TrapMemLimitExceeded),
-            tracker: vec![(0, None); ContractCostType::variants().len()],
+            ledger_io: Default::default(),
+            tracker: vec![(0, None); CostType::count()],
             enabled: true,
+            exceeded: None,
         };
 
         for ct in ContractCostType::variants() {
@@ -764,6 +1281,7 @@ impl Default for BudgetImpl {
 
             b.init_tracker();
         }
+        b.init_host_models();
 
         // For the time being we don't have "on chain" cost models
         // so we just set some up here that we calibrated manually
@@ -1,11 +1,15 @@
 use std::hint::black_box;
 
-use crate::{budget::AsBudget, cost_runner::CostRunner, xdr::ContractCostType};
+use crate::{
+    budget::{AsBudget, CostType},
+    cost_runner::CostRunner,
+    xdr::ContractCostType,
+};
 
 pub struct ChargeBudgetRun;
 
 impl CostRunner for ChargeBudgetRun {
-    const COST_TYPE: ContractCostType = ContractCostType::ChargeBudget;
+    const COST_TYPE: CostType = CostType::Contract(ContractCostType::ChargeBudget);
 
     const RUN_ITERATIONS: u64 = 1000;
 
@@ -0,0 +1,33 @@
+use std::hint::black_box;
+
+use crate::{
+    budget::{CostType, HostCostType},
+    cost_runner::CostRunner,
+};
+
+pub struct ComputeBlake2bHashRun;
+
+impl CostRunner for ComputeBlake2bHashRun {
+    const COST_TYPE: CostType = CostType::Host(HostCostType::ComputeBlake2bHash);
+
+    type SampleType = Vec<u8>;
+
+    type RecycledType = (Option<Vec<u8>>, Vec<u8>);
+
+    fn run_iter(host: &crate::Host, _iter: u64, sample: Self::SampleType) -> Self::RecycledType {
+        let hash = black_box(
+            host.blake2b_hash_from_bytes(sample.as_slice())
+                .expect("blake2b"),
+        );
+        (Some(hash), sample)
+    }
+
+    fn run_baseline_iter(
+        host: &crate::Host,
+        _iter: u64,
+        sample: Self::SampleType,
+    ) -> Self::RecycledType {
+        black_box(host.charge_budget(Self::COST_TYPE, Some(0)).unwrap());
+        black_box((None, sample))
+    }
+}
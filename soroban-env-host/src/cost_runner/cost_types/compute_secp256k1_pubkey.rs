@@ -0,0 +1,35 @@
+use std::hint::black_box;
+
+use k256::ecdsa::VerifyingKey;
+
+use crate::{
+    budget::{CostType, HostCostType},
+    cost_runner::CostRunner,
+};
+
+pub struct ComputeSecp256k1PubKeyRun;
+
+impl CostRunner for ComputeSecp256k1PubKeyRun {
+    const COST_TYPE: CostType = CostType::Host(HostCostType::ComputeSecp256k1PubKey);
+
+    type SampleType = Vec<u8>;
+
+    type RecycledType = (Option<VerifyingKey>, Vec<u8>);
+
+    fn run_iter(host: &crate::Host, _iter: u64, sample: Self::SampleType) -> Self::RecycledType {
+        let pk = black_box(
+            host.secp256k1_pub_key_from_bytes(sample.as_slice())
+                .expect("publickey"),
+        );
+        (Some(pk), sample)
+    }
+
+    fn run_baseline_iter(
+        host: &crate::Host,
+        _iter: u64,
+        sample: Self::SampleType,
+    ) -> Self::RecycledType {
+        black_box(host.charge_budget(Self::COST_TYPE, None).unwrap());
+        black_box((None, sample))
+    }
+}
@@ -1,13 +1,14 @@
 use std::hint::black_box;
 
 use crate::{
-    cost_runner::CostRunner, host::Frame, xdr::ContractCostType, xdr::Hash, Status, Symbol,
+    budget::CostType, cost_runner::CostRunner, host::Frame, xdr::ContractCostType, xdr::Hash,
+    Status, Symbol,
 };
 
 pub struct GuardFrameRun;
 
 impl CostRunner for GuardFrameRun {
-    const COST_TYPE: ContractCostType = ContractCostType::GuardFrame;
+    const COST_TYPE: CostType = CostType::Contract(ContractCostType::GuardFrame);
 
     type SampleType = (Hash, Symbol);
 
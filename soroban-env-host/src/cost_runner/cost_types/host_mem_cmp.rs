@@ -2,11 +2,15 @@ use std::{cmp::Ordering, hint::black_box};
 
 use soroban_env_common::Compare;
 
-use crate::{budget::AsBudget, cost_runner::CostRunner, xdr::ContractCostType};
+use crate::{
+    budget::{AsBudget, CostType},
+    cost_runner::CostRunner,
+    xdr::ContractCostType,
+};
 
 pub struct HostMemCmpRun;
 impl CostRunner for HostMemCmpRun {
-    const COST_TYPE: ContractCostType = ContractCostType::HostMemCmp;
+    const COST_TYPE: CostType = CostType::Contract(ContractCostType::HostMemCmp);
 
     type SampleType = (Vec<u8>, Vec<u8>);
 
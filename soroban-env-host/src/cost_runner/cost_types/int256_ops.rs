@@ -0,0 +1,94 @@
+use std::hint::black_box;
+
+use crate::{
+    budget::{CostType, HostCostType},
+    cost_runner::CostRunner,
+    Host, I256,
+};
+
+// These measure the arithmetic alone, as the host functions charge the
+// conversions of their operands and results separately.
+
+pub struct Int256AddSubRun;
+
+impl CostRunner for Int256AddSubRun {
+    const COST_TYPE: CostType = CostType::Host(HostCostType::Int256AddSub);
+
+    type SampleType = (I256, I256);
+
+    type RecycledType = (Option<I256>, Self::SampleType);
+
+    fn run_iter(host: &Host, _iter: u64, sample: Self::SampleType) -> Self::RecycledType {
+        black_box(host.charge_budget(Self::COST_TYPE, None).unwrap());
+        let res = black_box(sample.0.checked_sub(sample.1));
+        (res, sample)
+    }
+
+    fn run_baseline_iter(host: &Host, _iter: u64, sample: Self::SampleType) -> Self::RecycledType {
+        black_box(host.charge_budget(Self::COST_TYPE, None).unwrap());
+        black_box((None, sample))
+    }
+}
+
+pub struct Int256MulRun;
+
+impl CostRunner for Int256MulRun {
+    const COST_TYPE: CostType = CostType::Host(HostCostType::Int256Mul);
+
+    type SampleType = (I256, I256);
+
+    type RecycledType = (Option<I256>, Self::SampleType);
+
+    fn run_iter(host: &Host, _iter: u64, sample: Self::SampleType) -> Self::RecycledType {
+        black_box(host.charge_budget(Self::COST_TYPE, None).unwrap());
+        let res = black_box(sample.0.checked_mul(sample.1));
+        (res, sample)
+    }
+
+    fn run_baseline_iter(host: &Host, _iter: u64, sample: Self::SampleType) -> Self::RecycledType {
+        black_box(host.charge_budget(Self::COST_TYPE, None).unwrap());
+        black_box((None, sample))
+    }
+}
+
+pub struct Int256DivRun;
+
+impl CostRunner for Int256DivRun {
+    const COST_TYPE: CostType = CostType::Host(HostCostType::Int256Div);
+
+    type SampleType = (I256, I256);
+
+    type RecycledType = (Option<I256>, Self::SampleType);
+
+    fn run_iter(host: &Host, _iter: u64, sample: Self::SampleType) -> Self::RecycledType {
+        black_box(host.charge_budget(Self::COST_TYPE, None).unwrap());
+        let res = black_box(sample.0.checked_div(sample.1));
+        (res, sample)
+    }
+
+    fn run_baseline_iter(host: &Host, _iter: u64, sample: Self::SampleType) -> Self::RecycledType {
+        black_box(host.charge_budget(Self::COST_TYPE, None).unwrap());
+        black_box((None, sample))
+    }
+}
+
+pub struct Int256ShiftRun;
+
+impl CostRunner for Int256ShiftRun {
+    const COST_TYPE: CostType = CostType::Host(HostCostType::Int256Shift);
+
+    type SampleType = (I256, u32);
+
+    type RecycledType = (Option<I256>, Self::SampleType);
+
+    fn run_iter(host: &Host, _iter: u64, sample: Self::SampleType) -> Self::RecycledType {
+        black_box(host.charge_budget(Self::COST_TYPE, None).unwrap());
+        let res = black_box(sample.0.checked_shr(sample.1));
+        (res, sample)
+    }
+
+    fn run_baseline_iter(host: &Host, _iter: u64, sample: Self::SampleType) -> Self::RecycledType {
+        black_box(host.charge_budget(Self::COST_TYPE, None).unwrap());
+        black_box((None, sample))
+    }
+}
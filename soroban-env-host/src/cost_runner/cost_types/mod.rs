@@ -1,17 +1,26 @@
 mod charge_budget;
+mod compute_blake2b_hash;
 mod compute_ed25519_pubkey;
+mod compute_keccak256_hash;
+mod compute_secp256k1_pubkey;
+mod compute_secp256r1_pubkey;
 mod compute_sha256_hash;
+mod compute_sha512_hash;
 mod guard_frame;
 mod host_mem_alloc;
 mod host_mem_cmp;
 mod host_mem_cpy;
+mod int256_ops;
 #[cfg(feature = "vm")]
 mod invoke;
 mod map_ops;
+mod recover_ecdsa_secp256k1_key;
 mod val_deser;
 mod val_ser;
 mod val_xdr_conv;
 mod vec_ops;
+mod verify_ecdsa_secp256k1_sig;
+mod verify_ecdsa_secp256r1_sig;
 mod verify_ed25519_sig;
 mod visit_object;
 #[cfg(feature = "vm")]
@@ -20,19 +29,28 @@ mod vm_ops;
 mod wasm_insn_exec;
 
 pub use charge_budget::*;
+pub use compute_blake2b_hash::*;
 pub use compute_ed25519_pubkey::*;
+pub use compute_keccak256_hash::*;
+pub use compute_secp256k1_pubkey::*;
+pub use compute_secp256r1_pubkey::*;
 pub use compute_sha256_hash::*;
+pub use compute_sha512_hash::*;
 pub use guard_frame::*;
 pub use host_mem_alloc::*;
 pub use host_mem_cmp::*;
 pub use host_mem_cpy::*;
+pub use int256_ops::*;
 #[cfg(feature = "vm")]
 pub use invoke::*;
 pub use map_ops::*;
+pub use recover_ecdsa_secp256k1_key::*;
 pub use val_deser::*;
 pub use val_ser::*;
 pub use val_xdr_conv::*;
 pub use vec_ops::*;
+pub use verify_ecdsa_secp256k1_sig::*;
+pub use verify_ecdsa_secp256r1_sig::*;
 pub use verify_ed25519_sig::*;
 pub use visit_object::*;
 #[cfg(feature = "vm")]
@@ -0,0 +1,47 @@
+use std::hint::black_box;
+
+use k256::ecdsa::{RecoveryId, Signature};
+
+use crate::{
+    budget::{CostType, HostCostType},
+    cost_runner::CostRunner,
+    xdr::ScBytes,
+};
+
+pub struct RecoverEcdsaSecp256k1KeyRun;
+
+#[derive(Clone)]
+pub struct RecoverEcdsaSecp256k1KeySample {
+    pub digest: [u8; 32],
+    pub sig: Signature,
+    pub recovery_id: RecoveryId,
+}
+
+impl CostRunner for RecoverEcdsaSecp256k1KeyRun {
+    const COST_TYPE: CostType = CostType::Host(HostCostType::RecoverEcdsaSecp256k1Key);
+
+    type SampleType = RecoverEcdsaSecp256k1KeySample;
+
+    type RecycledType = (Option<ScBytes>, Self::SampleType);
+
+    fn run_iter(host: &crate::Host, _iter: u64, sample: Self::SampleType) -> Self::RecycledType {
+        let key = black_box(
+            host.recover_key_ecdsa_secp256k1_internal(
+                &sample.digest,
+                &sample.sig,
+                sample.recovery_id,
+            )
+            .expect("recover key secp256k1"),
+        );
+        (Some(key), sample)
+    }
+
+    fn run_baseline_iter(
+        host: &crate::Host,
+        _iter: u64,
+        sample: Self::SampleType,
+    ) -> Self::RecycledType {
+        black_box(host.charge_budget(Self::COST_TYPE, None).unwrap());
+        black_box((None, sample))
+    }
+}
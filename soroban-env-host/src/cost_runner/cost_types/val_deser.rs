@@ -1,11 +1,11 @@
 use std::hint::black_box;
 
-use crate::{cost_runner::CostRunner, xdr::ContractCostType, xdr::ScVal};
+use crate::{budget::CostType, cost_runner::CostRunner, xdr::ContractCostType, xdr::ScVal};
 
 pub struct ValDeserRun;
 
 impl CostRunner for ValDeserRun {
-    const COST_TYPE: ContractCostType = ContractCostType::ValDeser;
+    const COST_TYPE: CostType = CostType::Contract(ContractCostType::ValDeser);
 
     type SampleType = Vec<u8>;
 
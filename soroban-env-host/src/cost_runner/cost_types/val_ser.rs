@@ -1,11 +1,11 @@
 use std::hint::black_box;
 
-use crate::{cost_runner::CostRunner, xdr::ContractCostType, xdr::ScVal};
+use crate::{budget::CostType, cost_runner::CostRunner, xdr::ContractCostType, xdr::ScVal};
 
 pub struct ValSerRun;
 
 impl CostRunner for ValSerRun {
-    const COST_TYPE: ContractCostType = ContractCostType::ValSer;
+    const COST_TYPE: CostType = CostType::Contract(ContractCostType::ValSer);
 
     type SampleType = (ScVal, Vec<u8>);
 
@@ -1,6 +1,6 @@
 use std::hint::black_box;
 
-use crate::budget::AsBudget;
+use crate::budget::{AsBudget, CostType};
 use crate::{cost_runner::CostRunner, xdr::ContractCostType, Host, MeteredVector, RawVal};
 
 type HostVec = MeteredVector<RawVal>;
@@ -13,7 +13,7 @@ pub struct VecEntrySample {
     pub idxs: Vec<usize>,
 }
 impl CostRunner for VecEntryRun {
-    const COST_TYPE: ContractCostType = ContractCostType::VecEntry;
+    const COST_TYPE: CostType = CostType::Contract(ContractCostType::VecEntry);
 
     type SampleType = VecEntrySample;
 
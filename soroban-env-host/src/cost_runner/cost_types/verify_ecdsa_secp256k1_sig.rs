@@ -0,0 +1,42 @@
+use std::hint::black_box;
+
+use k256::ecdsa::{Signature, VerifyingKey};
+
+use crate::{
+    budget::{CostType, HostCostType},
+    cost_runner::CostRunner,
+};
+
+pub struct VerifyEcdsaSecp256k1SigRun;
+
+#[derive(Clone)]
+pub struct VerifyEcdsaSecp256k1SigSample {
+    pub key: VerifyingKey,
+    pub digest: [u8; 32],
+    pub sig: Signature,
+}
+
+impl CostRunner for VerifyEcdsaSecp256k1SigRun {
+    const COST_TYPE: CostType = CostType::Host(HostCostType::VerifyEcdsaSecp256k1Sig);
+
+    type SampleType = VerifyEcdsaSecp256k1SigSample;
+
+    type RecycledType = Self::SampleType;
+
+    fn run_iter(host: &crate::Host, _iter: u64, sample: Self::SampleType) -> Self::RecycledType {
+        black_box(
+            host.verify_sig_ecdsa_secp256k1_internal(&sample.digest, &sample.key, &sample.sig)
+                .expect("verify sig secp256k1"),
+        );
+        sample
+    }
+
+    fn run_baseline_iter(
+        host: &crate::Host,
+        _iter: u64,
+        sample: Self::SampleType,
+    ) -> Self::RecycledType {
+        black_box(host.charge_budget(Self::COST_TYPE, None).unwrap());
+        black_box(sample)
+    }
+}
@@ -1,11 +1,14 @@
 use std::hint::black_box;
 
-use crate::{cost_runner::CostRunner, host_object::HostObject, xdr::ContractCostType, Object};
+use crate::{
+    budget::CostType, cost_runner::CostRunner, host_object::HostObject, xdr::ContractCostType,
+    Object,
+};
 
 pub struct VisitObjectRun;
 
 impl CostRunner for VisitObjectRun {
-    const COST_TYPE: ContractCostType = ContractCostType::VisitObject;
+    const COST_TYPE: CostType = CostType::Contract(ContractCostType::VisitObject);
 
     type SampleType = Vec<Object>;
 
@@ -1,10 +1,13 @@
 use soroban_env_common::{BytesObject, VecObject};
 
-use super::{DebugEvent, Event, Events, HostEvent};
+use super::{
+    ContractEventsLimits, DebugError, DebugEvent, Event, Events, HostEvent,
+    CONTRACT_EVENTS_LIMIT_EXCEEDED,
+};
 use crate::{
     budget::{AsBudget, Budget},
     xdr,
-    xdr::ScVal,
+    xdr::{ContractCostType, ScVal},
     Host, HostError, RawVal,
 };
 
@@ -61,16 +64,53 @@ pub(crate) enum EventStatus {
 pub(crate) struct InternalEventsBuffer {
     //the bool keeps track of if the call this event was emitted in failed
     pub(crate) vec: Vec<(InternalEvent, EventStatus)>,
+    pub(crate) limits: ContractEventsLimits,
+    // Totals of the contract events recorded so far, including the ones
+    // from the failed calls.
+    contract_events_count: u32,
+    contract_events_size_bytes: u32,
 }
 
 impl InternalEventsBuffer {
     // Records an InternalEvent
-    pub fn record(&mut self, e: InternalEvent, _budget: &Budget) -> Result<(), HostError> {
-        //TODO:Add metering for non-diagnostic events
+    pub fn record(&mut self, e: InternalEvent, budget: &Budget) -> Result<(), HostError> {
+        // Diagnostic events are either metered when created or don't affect
+        // metering at all, but contract events are stored until the host is
+        // finished.
+        if let InternalEvent::Contract(_) = e {
+            budget.charge(
+                ContractCostType::HostMemAlloc,
+                Some(std::mem::size_of::<InternalEvent>() as u64),
+            )?;
+        }
         self.vec.push((e, EventStatus::FromSuccessfulCall));
         Ok(())
     }
 
+    // Accounts for a contract event of the given XDR size against the
+    // limits. This should be called before the event is recorded. The error
+    // is returned as a `DebugError`, as the buffer can't record it while
+    // being borrowed.
+    pub fn charge_contract_event(&mut self, size_bytes: u32) -> Result<(), DebugError> {
+        let count = self.contract_events_count.saturating_add(1);
+        if count > self.limits.max_count {
+            return Err(DebugError::new(CONTRACT_EVENTS_LIMIT_EXCEEDED)
+                .msg("contract events count exceeds the limit")
+                .arg(RawVal::from(count))
+                .arg(RawVal::from(self.limits.max_count)));
+        }
+        let size_bytes = self.contract_events_size_bytes.saturating_add(size_bytes);
+        if size_bytes > self.limits.max_size_bytes {
+            return Err(DebugError::new(CONTRACT_EVENTS_LIMIT_EXCEEDED)
+                .msg("contract events size exceeds the limit")
+                .arg(RawVal::from(size_bytes))
+                .arg(RawVal::from(self.limits.max_size_bytes)));
+        }
+        self.contract_events_count = count;
+        self.contract_events_size_bytes = size_bytes;
+        Ok(())
+    }
+
     /// Rolls back the event buffer starting at `events`.
     pub fn rollback(&mut self, events: usize) -> Result<(), HostError> {
         // note that we first skip the events that are not being rolled back
@@ -81,23 +121,10 @@ impl InternalEventsBuffer {
         Ok(())
     }
 
-    #[allow(unused)]
-    pub fn dump_to_debug_log(&self) {
-        use log::debug;
-        debug!("=======Start of events=======");
-        for e in self.vec.iter() {
-            match &e.0 {
-                InternalEvent::Contract(c) => debug!("Contract event: {:?}", c),
-                InternalEvent::Debug(d) => debug!("Debug event: {}", d),
-                InternalEvent::StructuredDebug(c) => debug!("StructuredDebug event: {:?}", c),
-            }
-        }
-        debug!("========End of events========")
-    }
-
     /// Converts the internal events into their external representation. This should only be called
     /// either when the host is finished (via `try_finish`), or when an error occurs.
-    // TODO: Metering for non-diagnostic events?
+    // The conversion of contract events is metered by its components, while
+    // diagnostic events are converted for free.
     pub fn externalize(&self, host: &Host) -> Result<Events, HostError> {
         let vec: Result<Vec<HostEvent>, HostError> = self
             .vec
@@ -0,0 +1,44 @@
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use super::{Event, HostEvent};
+use crate::xdr::ContractEventType;
+
+// The JSON rendering of a host event is kept independent from the layout of
+// the Rust types, so that it can be relied upon by the tools consuming it:
+//
+//   {"type": "contract" | "system" | "diagnostic", "failed_call": bool,
+//    "contract_id": hex string | null, "topics": [ScVal], "data": ScVal}
+//
+// or, for the debug events:
+//
+//   {"type": "debug", "failed_call": bool, "message": string}
+impl Serialize for HostEvent {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match &self.event {
+            Event::Debug(de) => {
+                let mut s = serializer.serialize_struct("HostEvent", 3)?;
+                s.serialize_field("type", "debug")?;
+                s.serialize_field("failed_call", &self.failed_call)?;
+                s.serialize_field("message", &de.to_string())?;
+                s.end()
+            }
+            Event::Contract(ce) | Event::StructuredDebug(ce) => {
+                let type_ = match ce.type_ {
+                    ContractEventType::System => "system",
+                    ContractEventType::Contract => "contract",
+                    ContractEventType::Diagnostic => "diagnostic",
+                };
+                let mut s = serializer.serialize_struct("HostEvent", 5)?;
+                s.serialize_field("type", type_)?;
+                s.serialize_field("failed_call", &self.failed_call)?;
+                s.serialize_field(
+                    "contract_id",
+                    &ce.contract_id.as_ref().map(|id| hex::encode(id.0)),
+                )?;
+                s.serialize_field("topics", &self.topics())?;
+                s.serialize_field("data", &self.data())?;
+                s.end()
+            }
+        }
+    }
+}
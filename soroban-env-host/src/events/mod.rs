@@ -1,11 +1,19 @@
 mod debug;
 mod internal;
+#[cfg(feature = "serde")]
+mod json;
+mod query;
 pub(crate) mod system_events;
+mod trace;
 
 pub use debug::{DebugArg, DebugError, DebugEvent};
 pub(crate) use internal::InternalEventsBuffer;
 // expose them as pub use for benches
 pub use internal::{InternalContractEvent, InternalEvent};
+pub use query::EventFilter;
+pub use trace::{EventsTrace, ScValDisplay};
+
+use crate::xdr::ScHostContextErrorCode;
 
 /// The external representation of a host event.
 // TODO: optimize storage on this to use pools / bumpalo / etc.
@@ -25,8 +33,36 @@ pub enum Event {
     StructuredDebug(crate::xdr::ContractEvent),
 }
 
+/// The error code of exceeding a [ContractEventsLimits] limit. The XDR
+/// doesn't have a dedicated code for the event limits, so they fail with the
+/// generic error of the host context the events are recorded in, which keeps
+/// them apart from the ledger IO and budget limits.
+pub const CONTRACT_EVENTS_LIMIT_EXCEEDED: ScHostContextErrorCode =
+    ScHostContextErrorCode::UnknownError;
+
+/// Limits on the contract events recorded by a host. The events emitted by
+/// the calls that have failed still count towards the limits. Exceeding a
+/// limit fails with [CONTRACT_EVENTS_LIMIT_EXCEEDED].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContractEventsLimits {
+    /// Maximum number of contract events.
+    pub max_count: u32,
+    /// Maximum total size of the XDR-serialized contract events.
+    pub max_size_bytes: u32,
+}
+
+impl Default for ContractEventsLimits {
+    fn default() -> Self {
+        Self {
+            max_count: u32::MAX,
+            max_size_bytes: u32::MAX,
+        }
+    }
+}
+
 /// The external representation of events in the chronological order.
 #[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Events(pub Vec<HostEvent>);
 
 // Maximum number of topics in a `ContractEvent`. This applies to both
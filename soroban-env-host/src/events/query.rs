@@ -0,0 +1,115 @@
+use super::{Event, Events, HostEvent};
+use crate::xdr::{ContractEvent, ContractEventBody, ContractEventType, Hash, ScVal};
+
+/// Selects [HostEvent]s out of [Events]. The criteria left unset match all
+/// the events, so the default filter matches every event.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EventFilter {
+    /// Only match the events emitted on behalf of this contract.
+    pub contract_id: Option<Hash>,
+    /// Only match the contract events of this type. Debug events have no
+    /// type, so they never match a filter setting one.
+    pub event_type: Option<ContractEventType>,
+    /// Only match the events whose leading topics match these, in order. A
+    /// `None` matches any topic in its position.
+    pub topics: Vec<Option<ScVal>>,
+    /// Only match the events emitted from calls that have failed (`true`)
+    /// or from the ones that haven't (`false`).
+    pub failed_call: Option<bool>,
+}
+
+impl EventFilter {
+    pub fn matches(&self, event: &HostEvent) -> bool {
+        if let Some(failed_call) = self.failed_call {
+            if event.failed_call != failed_call {
+                return false;
+            }
+        }
+        if self.contract_id.is_none() && self.event_type.is_none() && self.topics.is_empty() {
+            return true;
+        }
+        let Some(ce) = event.contract_event() else {
+            return false;
+        };
+        if let Some(contract_id) = &self.contract_id {
+            if ce.contract_id.as_ref() != Some(contract_id) {
+                return false;
+            }
+        }
+        if let Some(event_type) = self.event_type {
+            if ce.type_ != event_type {
+                return false;
+            }
+        }
+        let topics = event.topics().unwrap_or_default();
+        self.topics.len() <= topics.len()
+            && self
+                .topics
+                .iter()
+                .zip(topics.iter())
+                .all(|(pattern, topic)| pattern.as_ref().map_or(true, |p| p == topic))
+    }
+}
+
+impl HostEvent {
+    /// Returns the contract event carried by this event, which is any event
+    /// but the debug ones.
+    pub fn contract_event(&self) -> Option<&ContractEvent> {
+        match &self.event {
+            Event::Contract(ce) | Event::StructuredDebug(ce) => Some(ce),
+            Event::Debug(_) => None,
+        }
+    }
+
+    pub fn topics(&self) -> Option<&[ScVal]> {
+        let ContractEventBody::V0(v0) = &self.contract_event()?.body;
+        Some(v0.topics.as_slice())
+    }
+
+    pub fn data(&self) -> Option<&ScVal> {
+        let ContractEventBody::V0(v0) = &self.contract_event()?.body;
+        Some(&v0.data)
+    }
+
+    /// Converts the topics of this event into `T`s, returning `None` for
+    /// the debug events and if any of the topics isn't a `T`.
+    pub fn topics_as<T>(&self) -> Option<Vec<T>>
+    where
+        T: TryFrom<ScVal>,
+    {
+        self.topics()?
+            .iter()
+            .map(|t| T::try_from(t.clone()).ok())
+            .collect()
+    }
+
+    /// Converts the data of this event into a `T`, returning `None` for the
+    /// debug events and for the data that isn't a `T`.
+    pub fn data_as<T>(&self) -> Option<T>
+    where
+        T: TryFrom<ScVal>,
+    {
+        T::try_from(self.data()?.clone()).ok()
+    }
+}
+
+impl Events {
+    /// Returns the events matching `filter`, in the order they have been
+    /// emitted.
+    pub fn filter<'a>(&'a self, filter: &'a EventFilter) -> impl Iterator<Item = &'a HostEvent> {
+        self.0.iter().filter(move |e| filter.matches(e))
+    }
+
+    /// Returns the events of type `Contract` emitted by `contract_id` from
+    /// the calls that haven't failed, i.e. the ones reported in the
+    /// transaction meta.
+    pub fn contract_events_of(&self, contract_id: &Hash) -> Vec<&HostEvent> {
+        let filter = EventFilter {
+            contract_id: Some(contract_id.clone()),
+            event_type: Some(ContractEventType::Contract),
+            failed_call: Some(false),
+            ..Default::default()
+        };
+        self.filter(&filter).collect()
+    }
+}
@@ -0,0 +1,189 @@
+use std::fmt::{Display, Formatter, Result};
+
+use soroban_env_common::num::{i256_from_pieces, u256_from_pieces};
+
+use super::{Event, Events, HostEvent};
+use crate::xdr::{ContractEvent, ContractEventType, ScAddress, ScVal};
+
+/// Renders [Events] as an indented execution trace, with one line per event.
+///
+/// The contract calls and returns are reconstructed from the diagnostic
+/// events, so the calls are only rendered for the hosts recording them (see
+/// [DiagnosticLevel](crate::DiagnosticLevel)), and their failures only at
+/// the `Trace` level. The events emitted from the calls that have failed are
+/// marked as rolled back, and the calls that have not finished by the end of
+/// the events, such as the ones unwound by a [HostError](crate::HostError),
+/// are listed at the end.
+pub struct EventsTrace<'a>(pub(crate) &'a Events);
+
+impl Events {
+    pub fn trace(&self) -> EventsTrace<'_> {
+        EventsTrace(self)
+    }
+}
+
+impl Display for EventsTrace<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        // The calls that have been entered and haven't returned yet.
+        let mut calls: Vec<String> = vec![];
+        for e in self.0 .0.iter() {
+            let depth = calls.len();
+            let rolled_back = if e.failed_call { " (rolled back)" } else { "" };
+            let topics = e.topics().unwrap_or_default();
+            let data = e.data().unwrap_or(&ScVal::Void);
+            let is_diagnostic = matches!(e.event, Event::StructuredDebug(_));
+            match topics {
+                [ScVal::Symbol(s), ScVal::Bytes(id), func]
+                    if is_diagnostic && s.as_slice() == b"fn_call" =>
+                {
+                    let call = format!("{}:{}", hex::encode(id.as_slice()), ScValDisplay(func));
+                    let args = match data {
+                        ScVal::Vec(Some(args)) => join(args.iter()),
+                        _ => String::new(),
+                    };
+                    writeln!(
+                        f,
+                        "{:indent$}call {}({}){}",
+                        "",
+                        call,
+                        args,
+                        rolled_back,
+                        indent = depth * 2
+                    )?;
+                    calls.push(call);
+                }
+                [ScVal::Symbol(s), _] if is_diagnostic && s.as_slice() == b"fn_return" => {
+                    let call = calls.pop().unwrap_or_default();
+                    writeln!(
+                        f,
+                        "{:indent$}return {} -> {}{}",
+                        "",
+                        call,
+                        ScValDisplay(data),
+                        rolled_back,
+                        indent = calls.len() * 2
+                    )?;
+                }
+                [ScVal::Symbol(s), _] if is_diagnostic && s.as_slice() == b"fn_error" => {
+                    let call = calls.pop().unwrap_or_default();
+                    writeln!(
+                        f,
+                        "{:indent$}! {} failed with {}{}",
+                        "",
+                        call,
+                        ScValDisplay(data),
+                        rolled_back,
+                        indent = calls.len() * 2
+                    )?;
+                }
+                _ => writeln!(f, "{:indent$}{}", "", e.to_trace_line(), indent = depth * 2)?,
+            }
+        }
+        for (depth, call) in calls.iter().enumerate().rev() {
+            writeln!(
+                f,
+                "{:indent$}! {} did not return",
+                "",
+                call,
+                indent = depth * 2
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn event_type_name(ce: &ContractEvent) -> &'static str {
+    match ce.type_ {
+        ContractEventType::System => "system",
+        ContractEventType::Contract => "contract",
+        ContractEventType::Diagnostic => "diagnostic",
+    }
+}
+
+fn contract_suffix(ce: &ContractEvent) -> String {
+    match &ce.contract_id {
+        Some(id) => format!(" from {}", hex::encode(id.0)),
+        None => String::new(),
+    }
+}
+
+fn join<'a>(vals: impl Iterator<Item = &'a ScVal>) -> String {
+    vals.map(|v| ScValDisplay(v).to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Formats an [ScVal] the way it would be written in a contract, with the
+/// integer types as suffixes and the binary values in hex.
+pub struct ScValDisplay<'a>(pub &'a ScVal);
+
+impl Display for ScValDisplay<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self.0 {
+            ScVal::Bool(b) => write!(f, "{}", b),
+            ScVal::Void => write!(f, "()"),
+            ScVal::Status(st) => write!(f, "{:?}", st),
+            ScVal::U32(u) => write!(f, "{}u32", u),
+            ScVal::I32(i) => write!(f, "{}i32", i),
+            ScVal::U64(u) => write!(f, "{}u64", u),
+            ScVal::I64(i) => write!(f, "{}i64", i),
+            ScVal::Timepoint(t) => write!(f, "Timepoint({})", t.0),
+            ScVal::Duration(d) => write!(f, "Duration({})", d.0),
+            ScVal::U128(u) => write!(f, "{}u128", u128::from(u)),
+            ScVal::I128(i) => write!(f, "{}i128", i128::from(i)),
+            ScVal::U256(u) => write!(
+                f,
+                "{}u256",
+                u256_from_pieces(u.hi_hi, u.hi_lo, u.lo_hi, u.lo_lo)
+            ),
+            ScVal::I256(i) => write!(
+                f,
+                "{}i256",
+                i256_from_pieces(i.hi_hi, i.hi_lo, i.lo_hi, i.lo_lo)
+            ),
+            ScVal::Bytes(b) => write!(f, "0x{}", hex::encode(b.as_slice())),
+            ScVal::String(s) => write!(f, "{:?}", String::from_utf8_lossy(s.as_slice())),
+            ScVal::Symbol(s) => write!(f, "{}", String::from_utf8_lossy(s.as_slice())),
+            ScVal::Vec(Some(v)) => write!(f, "[{}]", join(v.iter())),
+            ScVal::Map(Some(m)) => {
+                let entries: Vec<String> = m
+                    .iter()
+                    .map(|e| format!("{}: {}", ScValDisplay(&e.key), ScValDisplay(&e.val)))
+                    .collect();
+                write!(f, "{{{}}}", entries.join(", "))
+            }
+            ScVal::Address(ScAddress::Contract(id)) => {
+                write!(f, "Contract({})", hex::encode(id.0))
+            }
+            ScVal::Vec(None)
+            | ScVal::Map(None)
+            | ScVal::Address(_)
+            | ScVal::ContractExecutable(_)
+            | ScVal::LedgerKeyContractExecutable
+            | ScVal::LedgerKeyNonce(_) => write!(f, "{:?}", self.0),
+        }
+    }
+}
+
+impl HostEvent {
+    /// Renders this event as a single line of a trace. Unlike
+    /// [EventsTrace], this doesn't interpret the call events.
+    pub fn to_trace_line(&self) -> String {
+        let rolled_back = if self.failed_call {
+            " (rolled back)"
+        } else {
+            ""
+        };
+        match &self.event {
+            Event::Debug(de) => format!("debug: {}{}", de, rolled_back),
+            Event::Contract(ce) | Event::StructuredDebug(ce) => format!(
+                "{} event{}: [{}] {}{}",
+                event_type_name(ce),
+                contract_suffix(ce),
+                join(self.topics().unwrap_or_default().iter()),
+                ScValDisplay(self.data().unwrap_or(&ScVal::Void)),
+                rolled_back
+            ),
+        }
+    }
+}
@@ -7,7 +7,12 @@ const INSTRUCTIONS_INCREMENT: i64 = 10000;
 const DATA_SIZE_1KB_INCREMENT: i64 = 1024;
 const TX_BASE_RESULT_SIZE: u32 = 300;
 
+use crate::budget::Budget;
+
 /// These are the resource upper bounds specified by the Soroban transaction.
+///
+/// The resources actually consumed by a host run can be obtained with
+/// [compute_transaction_resources].
 pub struct TransactionResources {
     /// Number of CPU instructions.
     pub instructions: u32,
@@ -29,6 +34,37 @@ pub struct TransactionResources {
     pub transaction_size_bytes: u32,
 }
 
+/// Computes the resources consumed by a finished host run, i.e. from the
+/// [Budget] returned by [Host::try_finish](crate::Host::try_finish), and the
+/// size of the transaction envelope XDR.
+///
+/// The resources are the ones metered by the [Budget] during the run (see
+/// [Budget::get_transaction_resources]). Instructions and sizes are
+/// increased by `safety_margin_percent` percent, so that the result can be
+/// used as the declared resources of the transaction even if its execution
+/// turns out slightly more expensive than the simulated one. The entry
+/// counts are exact and are not affected.
+pub fn compute_transaction_resources(
+    budget: &Budget,
+    transaction_size_bytes: u32,
+    safety_margin_percent: u32,
+) -> TransactionResources {
+    let resources = budget.get_transaction_resources(transaction_size_bytes);
+    let with_margin = |value: u32| -> u32 {
+        let value = u64::from(value)
+            .saturating_mul(100_u64.saturating_add(safety_margin_percent.into()))
+            / 100;
+        u32::try_from(value).unwrap_or(u32::MAX)
+    };
+    TransactionResources {
+        instructions: with_margin(resources.instructions),
+        read_bytes: with_margin(resources.read_bytes),
+        write_bytes: with_margin(resources.write_bytes),
+        metadata_size_bytes: with_margin(resources.metadata_size_bytes),
+        ..resources
+    }
+}
+
 /// Fee-related network configuration.
 ///
 /// This should be normally loaded from the ledger.
@@ -53,35 +89,79 @@ pub struct FeeConfiguration {
     pub fee_per_propagate_1kb: i64,
 }
 
+/// Itemized resource fee of a transaction, as computed by
+/// [compute_fee_breakdown].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FeeBreakdown {
+    /// Fee for the CPU instructions.
+    pub compute_fee: i64,
+    /// Fee for the ledger entries read (written entries included).
+    pub read_entry_fee: i64,
+    /// Fee for the ledger entries written.
+    pub write_entry_fee: i64,
+    /// Fee for the bytes read from the ledger.
+    pub read_bytes_fee: i64,
+    /// Fee for the bytes written to the ledger.
+    pub write_bytes_fee: i64,
+    /// Fee for the transaction and its result written to history.
+    pub historical_fee: i64,
+    /// Fee for the transaction metadata. This is the only refundable
+    /// component.
+    pub metadata_fee: i64,
+    /// Fee for propagating the transaction over the network.
+    pub bandwidth_fee: i64,
+}
+
+impl FeeBreakdown {
+    /// The part of the fee that is refunded if the transaction consumes less
+    /// than it has declared.
+    pub fn refundable_fee(&self) -> i64 {
+        self.metadata_fee
+    }
+
+    pub fn non_refundable_fee(&self) -> i64 {
+        self.compute_fee
+            .saturating_add(self.read_entry_fee)
+            .saturating_add(self.write_entry_fee)
+            .saturating_add(self.read_bytes_fee)
+            .saturating_add(self.write_bytes_fee)
+            .saturating_add(self.historical_fee)
+            .saturating_add(self.bandwidth_fee)
+    }
+
+    pub fn total_fee(&self) -> i64 {
+        self.refundable_fee()
+            .saturating_add(self.non_refundable_fee())
+    }
+}
+
 /// Computes the resource fee for a transaction based on the resource
-/// consumption and the fee-related network configuration.
-///
-/// Returns a pair of `(fee, refundable_fee)`, where refundable fee is also
-/// included into the final fee.
-pub fn compute_transaction_resource_fee(
+/// consumption and the fee-related network configuration, itemized by
+/// resource.
+pub fn compute_fee_breakdown(
     tx_resources: &TransactionResources,
     fee_config: &FeeConfiguration,
-) -> (i64, i64) {
+) -> FeeBreakdown {
     let compute_fee = compute_fee_per_increment(
         tx_resources.instructions,
         fee_config.fee_per_instruction_increment,
         INSTRUCTIONS_INCREMENT,
     );
-    let ledger_read_entry_fee: i64 = fee_config.fee_per_read_entry.saturating_mul(
+    let read_entry_fee: i64 = fee_config.fee_per_read_entry.saturating_mul(
         tx_resources
             .read_entries
             .saturating_add(tx_resources.write_entries)
             .into(),
     );
-    let ledger_write_entry_fee = fee_config
+    let write_entry_fee = fee_config
         .fee_per_write_entry
         .saturating_mul(tx_resources.write_entries.into());
-    let ledger_read_bytes_fee = compute_fee_per_increment(
+    let read_bytes_fee = compute_fee_per_increment(
         tx_resources.read_bytes,
         fee_config.fee_per_read_1kb,
         DATA_SIZE_1KB_INCREMENT,
     );
-    let ledger_write_bytes_fee = compute_fee_per_increment(
+    let write_bytes_fee = compute_fee_per_increment(
         tx_resources.write_bytes,
         fee_config.fee_per_write_1kb,
         DATA_SIZE_1KB_INCREMENT,
@@ -95,7 +175,7 @@ pub fn compute_transaction_resource_fee(
         DATA_SIZE_1KB_INCREMENT,
     );
 
-    let meta_data_fee = compute_fee_per_increment(
+    let metadata_fee = compute_fee_per_increment(
         tx_resources.metadata_size_bytes,
         fee_config.fee_per_metadata_1kb,
         DATA_SIZE_1KB_INCREMENT,
@@ -107,19 +187,43 @@ pub fn compute_transaction_resource_fee(
         DATA_SIZE_1KB_INCREMENT,
     );
 
-    let refundable_fee = meta_data_fee;
-    let non_refundable_fee = compute_fee
-        .saturating_add(ledger_read_entry_fee)
-        .saturating_add(ledger_write_entry_fee)
-        .saturating_add(ledger_read_bytes_fee)
-        .saturating_add(ledger_write_bytes_fee)
-        .saturating_add(historical_fee)
-        .saturating_add(bandwidth_fee);
-    let res = (
-        refundable_fee.saturating_add(non_refundable_fee),
-        refundable_fee,
-    );
-    res
+    FeeBreakdown {
+        compute_fee,
+        read_entry_fee,
+        write_entry_fee,
+        read_bytes_fee,
+        write_bytes_fee,
+        historical_fee,
+        metadata_fee,
+        bandwidth_fee,
+    }
+}
+
+/// Computes the resource fee for a transaction based on the resource
+/// consumption and the fee-related network configuration.
+///
+/// Returns a pair of `(fee, refundable_fee)`, where refundable fee is also
+/// included into the final fee.
+pub fn compute_transaction_resource_fee(
+    tx_resources: &TransactionResources,
+    fee_config: &FeeConfiguration,
+) -> (i64, i64) {
+    let breakdown = compute_fee_breakdown(tx_resources, fee_config);
+    (breakdown.total_fee(), breakdown.refundable_fee())
+}
+
+/// Computes the amount refunded to a transaction that has declared
+/// `declared_resources` and actually consumed `consumed_resources`, i.e. the
+/// difference between the declared and consumed refundable fees. The refund
+/// is never negative: a transaction can't consume more than it has declared.
+pub fn compute_refund(
+    declared_resources: &TransactionResources,
+    consumed_resources: &TransactionResources,
+    fee_config: &FeeConfiguration,
+) -> i64 {
+    let declared = compute_fee_breakdown(declared_resources, fee_config).refundable_fee();
+    let consumed = compute_fee_breakdown(consumed_resources, fee_config).refundable_fee();
+    declared.saturating_sub(consumed).max(0)
 }
 
 fn compute_fee_per_increment(resource_value: u32, fee_rate: i64, increment: i64) -> i64 {
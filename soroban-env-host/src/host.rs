@@ -7,7 +7,10 @@ use core::fmt::Debug;
 use std::rc::Rc;
 
 use soroban_env_common::{
-    num::{i256_from_pieces, i256_into_pieces, u256_from_pieces, u256_into_pieces},
+    num::{
+        i128_mul_div, i256_from_pieces, i256_into_pieces, u256_from_pieces, u256_into_pieces,
+        u256_mul_div,
+    },
     xdr::{
         int128_helpers, AccountId, Asset, ContractCodeEntry, ContractCostType, ContractDataEntry,
         ContractEventType, ContractId, CreateContractArgs, ExtensionPoint, Hash, HashIdPreimage,
@@ -18,30 +21,34 @@ use soroban_env_common::{
         ScSymbol, ScUnknownErrorCode, ScVal, ScVec, UInt128Parts, UInt256Parts,
         UploadContractWasmArgs,
     },
-    AddressObject, Bool, BytesObject, Convert, I128Object, I256Object, I64Object, MapObject,
-    ScValObjRef, ScValObject, Status, StringObject, SymbolObject, SymbolSmall, TryFromVal,
-    TryIntoVal, U128Object, U256Object, U32Val, U64Object, U64Val, VecObject, VmCaller,
-    VmCallerEnv, Void, I256, U256,
+    AddressObject, Bool, BytesObject, Convert, I128Object, I128Val, I256Object, I256Val, I64Object,
+    MapObject, ScValObjRef, ScValObject, Status, StringObject, SymbolObject, SymbolSmall,
+    TryFromVal, TryIntoVal, U128Object, U128Val, U256Object, U256Val, U32Val, U64Object, U64Val,
+    VecObject, VmCaller, VmCallerEnv, Void, I256, U256,
 };
 
-use crate::auth::{AuthorizationManager, AuthorizationManagerSnapshot, RecordedAuthPayload};
+use crate::auth::{
+    AuthorizationManager, AuthorizationManagerSnapshot, ExpiringContractAuth, RecordedAuthPayload,
+};
 use crate::events::{
-    DebugError, DebugEvent, Events, InternalContractEvent, InternalEvent, InternalEventsBuffer,
+    ContractEventsLimits, DebugError, DebugEvent, Events, InternalContractEvent, InternalEvent,
+    InternalEventsBuffer,
 };
-use crate::storage::{Storage, StorageMap};
+use crate::storage::{ExpirationMap, Storage, StorageMap};
 use crate::{
-    budget::{AsBudget, Budget},
+    budget::{AsBudget, Budget, CostType, HostCostType, LedgerIoType},
     storage::{TempStorage, TempStorageMap},
 };
 
 use crate::host_object::{HostMap, HostObject, HostObjectType, HostVec};
 use crate::SymbolStr;
 #[cfg(feature = "vm")]
-use crate::Vm;
+use crate::{vm::ModuleCache, Vm};
 use crate::{EnvBase, Object, RawVal, Symbol};
 
 pub(crate) mod comparison;
 mod conversion;
+mod crypto;
 mod data_helper;
 pub(crate) mod declared_size;
 mod diagnostics_helper;
@@ -53,10 +60,14 @@ pub(crate) mod metered_clone;
 pub(crate) mod metered_map;
 pub(crate) mod metered_vector;
 pub(crate) mod metered_xdr;
+mod num;
+#[cfg(any(test, feature = "testutils"))]
+pub(crate) mod profiler;
 mod validity;
 pub use error::HostError;
 
 use self::metered_vector::MeteredVector;
+use self::num::{i128_mul_div_costs, u256_mul_div_costs};
 use self::{invoker_type::InvokerType, metered_clone::MeteredClone};
 use crate::Compare;
 
@@ -71,6 +82,8 @@ const RESERVED_CONTRACT_FN_PREFIX: &str = "__";
 #[derive(Clone)]
 struct RollbackPoint {
     storage: StorageMap,
+    expirations: ExpirationMap,
+    bumps: ExpirationMap,
     temp_storage: TempStorageMap,
     events: usize,
     auth: Option<AuthorizationManagerSnapshot>,
@@ -150,12 +163,26 @@ pub struct LedgerInfo {
     pub timestamp: u64,
     pub network_id: [u8; 32],
     pub base_reserve: u32,
+    /// Number of ledgers a newly created contract data entry lives for. Zero
+    /// means that the new entries never expire.
+    pub min_persistent_entry_expiration: u32,
+    /// Number of ledgers a newly created expiring contract data entry lives
+    /// for. Expiring entries always expire, so zero means that they only
+    /// live until the end of the current ledger.
+    pub min_temp_entry_expiration: u32,
+    /// Maximum number of ledgers (counting from the current one) an entry
+    /// can be bumped to live for. Zero means there is no limit.
+    pub max_entry_expiration: u32,
 }
 
 #[derive(Clone)]
 pub enum DiagnosticLevel {
     None,
     Debug,
+    /// Everything emitted at the `Debug` level, plus the events tracing the
+    /// host function calls made by the guests, contract data accesses and
+    /// authorization checks.
+    Trace,
 }
 
 impl Default for DiagnosticLevel {
@@ -180,6 +207,22 @@ pub(crate) struct HostImpl {
     pub(crate) events: RefCell<InternalEventsBuffer>,
     authorization_manager: RefCell<AuthorizationManager>,
     diagnostic_level: RefCell<DiagnosticLevel>,
+    // The modules are taken from this cache when set, instead of being parsed
+    // on every call (see `Host::set_module_cache`).
+    #[cfg(feature = "vm")]
+    module_cache: RefCell<Option<ModuleCache>>,
+    // The contracts whose instances are reused within an invocation (see
+    // `Host::set_vm_reuse`).
+    #[cfg(feature = "vm")]
+    vm_reuse_contracts: RefCell<std::collections::BTreeSet<Hash>>,
+    // The instances kept for reuse within the current invocation, with the
+    // hash of the code they've been instantiated from.
+    #[cfg(feature = "vm")]
+    pub(crate) reusable_vms: RefCell<std::collections::BTreeMap<Hash, (Hash, Rc<Vm>)>>,
+    // Whether the arguments of the calls to WASM contracts are checked
+    // against the contract spec (see `Host::set_contract_args_validation`).
+    #[cfg(feature = "vm")]
+    validate_contract_args: RefCell<bool>,
     // Note: we're not going to charge metering for testutils because it's out of the scope
     // of what users will be charged for in production -- it's scaffolding for testing a contract,
     // but shouldn't be charged to the contract itself (and will never be compiled-in to
@@ -194,6 +237,10 @@ pub(crate) struct HostImpl {
     // that allow checking if the authorization has been recorded.
     #[cfg(any(test, feature = "testutils"))]
     previous_authorization_manager: RefCell<Option<AuthorizationManager>>,
+    // Attributes the budget charges to the frames when cost profiling is
+    // enabled (see `Host::enable_cost_profiling`).
+    #[cfg(any(test, feature = "testutils"))]
+    profiler: RefCell<Option<profiler::CostProfiler>>,
 }
 // Host is a newtype on Rc<HostImpl> so we can impl Env for it below.
 #[derive(Default, Clone)]
@@ -257,10 +304,20 @@ impl Host {
                 AuthorizationManager::new_enforcing_without_authorizations(budget),
             ),
             diagnostic_level: Default::default(),
+            #[cfg(feature = "vm")]
+            module_cache: RefCell::new(None),
+            #[cfg(feature = "vm")]
+            vm_reuse_contracts: Default::default(),
+            #[cfg(feature = "vm")]
+            reusable_vms: Default::default(),
+            #[cfg(feature = "vm")]
+            validate_contract_args: RefCell::new(false),
             #[cfg(any(test, feature = "testutils"))]
             contracts: Default::default(),
             #[cfg(any(test, feature = "testutils"))]
             previous_authorization_manager: RefCell::new(None),
+            #[cfg(any(test, feature = "testutils"))]
+            profiler: RefCell::new(None),
         }))
     }
 
@@ -291,6 +348,24 @@ impl Host {
         Ok(())
     }
 
+    /// Same as [`Host::set_authorization_entries`], but the entries are
+    /// signed with an expiration ledger and use unique nonces instead of the
+    /// sequential ones (see [`ExpiringContractAuth`]).
+    pub fn set_expiring_authorization_entries(
+        &self,
+        auth_entries: Vec<ExpiringContractAuth>,
+    ) -> Result<(), HostError> {
+        let new_auth_manager = AuthorizationManager::new_enforcing_with_expiration(
+            self,
+            auth_entries
+                .into_iter()
+                .map(|e| (e.contract_auth, Some(e.signature_expiration_ledger)))
+                .collect(),
+        )?;
+        *self.0.authorization_manager.borrow_mut() = new_auth_manager;
+        Ok(())
+    }
+
     pub fn set_ledger_info(&self, info: LedgerInfo) {
         *self.0.ledger.borrow_mut() = Some(info)
     }
@@ -333,7 +408,11 @@ impl Host {
         self.0.budget.clone()
     }
 
-    pub fn charge_budget(&self, ty: ContractCostType, input: Option<u64>) -> Result<(), HostError> {
+    pub fn charge_budget(
+        &self,
+        ty: impl Into<CostType>,
+        input: Option<u64>,
+    ) -> Result<(), HostError> {
         self.0.budget.clone().charge(ty, input)
     }
 
@@ -341,8 +420,110 @@ impl Host {
         *self.0.diagnostic_level.borrow_mut() = diagnostic_level;
     }
 
+    /// Sets the limits on the number and the total size of the contract
+    /// events this host can record. Emitting an event past the limits fails
+    /// the call that emits it.
+    pub fn set_contract_events_limits(&self, limits: ContractEventsLimits) {
+        self.0.events.borrow_mut().limits = limits;
+    }
+
+    /// Makes the host take the WASM modules of the contracts it calls from
+    /// `cache`, and add the ones missing from it. The same cache can be set
+    /// on any number of hosts.
+    #[cfg(feature = "vm")]
+    pub fn set_module_cache(&self, cache: ModuleCache) {
+        *self.0.module_cache.borrow_mut() = Some(cache);
+    }
+
+    /// Makes the host keep the [Vm] it instantiates for the contract
+    /// `contract_id`, and reuse it for the subsequent calls to the contract
+    /// until the top-level invocation finishes, instead of instantiating the
+    /// contract on every call.
+    ///
+    /// Unlike a fresh instance, a reused one keeps the state of its linear
+    /// memory and globals from the previous calls, so this should only be
+    /// enabled for the contracts that don't rely on starting each call from
+    /// the initial state of their module. The re-entrant calls still get a
+    /// fresh instance, and all the kept instances are discarded whenever a
+    /// call fails, so that no instance carries state from a rolled back call.
+    ///
+    /// A reused instance isn't charged [ContractCostType::VmInstantiation]
+    /// again. Accessing it is charged as a [ContractCostType::VmMemRead] of no
+    /// bytes instead, which only costs the constant part of the model.
+    #[cfg(feature = "vm")]
+    pub fn set_vm_reuse(&self, contract_id: Hash, enabled: bool) {
+        if enabled {
+            self.0.vm_reuse_contracts.borrow_mut().insert(contract_id);
+        } else {
+            self.0.vm_reuse_contracts.borrow_mut().remove(&contract_id);
+            self.0.reusable_vms.borrow_mut().remove(&contract_id);
+        }
+    }
+
+    // Takes the instance kept for the contract out, if it's been instantiated
+    // from the current code of the contract. Taking it out while it runs is
+    // what makes the re-entrant calls instantiate the contract again.
+    #[cfg(feature = "vm")]
+    fn take_reusable_vm(&self, id: &Hash, wasm_hash: &Hash) -> Option<Rc<Vm>> {
+        match self.0.reusable_vms.borrow_mut().remove(id) {
+            Some((hash, vm)) if hash == *wasm_hash => Some(vm),
+            _ => None,
+        }
+    }
+
+    /// Makes the host check the arguments of the calls to WASM contracts
+    /// against the function signatures declared in the contract spec before
+    /// dispatching them, instead of leaving the contract to fail on an
+    /// unexpected value. The functions missing from the spec aren't checked.
+    #[cfg(feature = "vm")]
+    pub fn set_contract_args_validation(&self, enabled: bool) {
+        *self.0.validate_contract_args.borrow_mut() = enabled;
+    }
+
+    #[cfg(feature = "vm")]
+    fn check_contract_args(
+        &self,
+        vm: &Vm,
+        func: &Symbol,
+        args: &[RawVal],
+    ) -> Result<(), HostError> {
+        if !*self.0.validate_contract_args.borrow() {
+            return Ok(());
+        }
+        let spec = vm.metered_contract_spec(self)?;
+        let func_ss = SymbolStr::try_from_val(self, func)?;
+        let Some(func_spec) = spec.function(func_ss.as_ref()) else {
+            return Ok(());
+        };
+        let args = args
+            .iter()
+            .map(|a| self.from_host_val(*a))
+            .collect::<Result<Vec<ScVal>, HostError>>()?;
+        func_spec.check_args(self, &args)
+    }
+
+    #[cfg(feature = "vm")]
+    fn keep_reusable_vm(&self, id: &Hash, wasm_hash: Hash, vm: Rc<Vm>) {
+        // The instances are dropped once the call stack is empty, which also
+        // breaks the reference cycle between them and the host.
+        if self.0.context.borrow().is_empty() || !self.0.vm_reuse_contracts.borrow().contains(id) {
+            return;
+        }
+        self.0
+            .reusable_vms
+            .borrow_mut()
+            .insert(id.clone(), (wasm_hash, vm));
+    }
+
     pub fn is_debug(&self) -> bool {
-        matches!(*self.0.diagnostic_level.borrow(), DiagnosticLevel::Debug)
+        matches!(
+            *self.0.diagnostic_level.borrow(),
+            DiagnosticLevel::Debug | DiagnosticLevel::Trace
+        )
+    }
+
+    pub fn is_trace(&self) -> bool {
+        matches!(*self.0.diagnostic_level.borrow(), DiagnosticLevel::Trace)
     }
 
     pub(crate) fn get_events_mut<F, U>(&self, f: F) -> Result<U, HostError>
@@ -390,6 +571,17 @@ impl Host {
             topics,
             data,
         };
+        // Contract events end up in the transaction metadata, so their XDR
+        // size is charged as ledger IO, once it has been checked against the
+        // events limits. The size is only known once the event is serialized,
+        // which is charged as `ValSer` like any other serialization done by
+        // the host.
+        let mut buf = vec![];
+        self.metered_write_xdr(&ce.to_xdr(self)?, &mut buf)?;
+        self.get_events_mut(|events| Ok(events.charge_contract_event(buf.len() as u32)))?
+            .map_err(|e| self.err(e))?;
+        self.as_budget()
+            .charge_ledger_io(LedgerIoType::MetadataBytes, buf.len() as u64)?;
         self.get_events_mut(|events| {
             Ok(events.record(InternalEvent::Contract(ce), self.as_budget()))
         })?
@@ -440,9 +632,13 @@ impl Host {
             auth_snapshot = Some(auth_manager.snapshot());
         }
 
+        #[cfg(any(test, feature = "testutils"))]
+        self.profile_push_frame(&frame)?;
         self.0.context.borrow_mut().push(frame);
         Ok(RollbackPoint {
             storage: self.0.storage.borrow().map.clone(),
+            expirations: self.0.storage.borrow().expiration_map.clone(),
+            bumps: self.0.storage.borrow().bumps.clone(),
             temp_storage: self.0.temp_storage.borrow().map.clone(),
             events: self.0.events.borrow().vec.len(),
             auth: auth_snapshot,
@@ -453,6 +649,8 @@ impl Host {
     /// the current context and optionally rolls back the [`Host`]'s objects
     /// and storage map to the state in the provided [`RollbackPoint`].
     fn pop_frame(&self, orp: Option<RollbackPoint>) -> Result<(), HostError> {
+        #[cfg(any(test, feature = "testutils"))]
+        self.profile_pop_frame();
         self.0
             .context
             .borrow_mut()
@@ -484,10 +682,18 @@ impl Host {
                     Some(self.0.authorization_manager.borrow().clone());
                 self.0.authorization_manager.borrow_mut().reset();
             }
+            #[cfg(feature = "vm")]
+            self.0.reusable_vms.borrow_mut().clear();
         }
 
         if let Some(rp) = orp {
-            self.0.storage.borrow_mut().map = rp.storage;
+            // The kept instances may have run within the rolled back calls.
+            #[cfg(feature = "vm")]
+            self.0.reusable_vms.borrow_mut().clear();
+            let mut storage = self.0.storage.borrow_mut();
+            storage.map = rp.storage;
+            storage.expiration_map = rp.expirations;
+            storage.bumps = rp.bumps;
             self.0.events.borrow_mut().rollback(rp.events)?;
             if let Some(auth_rp) = rp.auth {
                 self.0.authorization_manager.borrow_mut().rollback(auth_rp);
@@ -549,6 +755,8 @@ impl Host {
             }
             res => res,
         };
+        // Attribute a budget exhaustion to the frame before it's gone.
+        let res = res.map_err(|e| self.attach_budget_exceeded(e));
         if res.is_err() {
             // Pop and rollback on error.
             self.pop_frame(Some(rp))?;
@@ -975,12 +1183,35 @@ impl Host {
             #[cfg(feature = "vm")]
             ScContractExecutable::WasmRef(wasm_hash) => {
                 let code_entry = self.retrieve_wasm_from_storage(&wasm_hash)?;
-                let vm = Vm::new(
-                    self,
-                    id.metered_clone(&self.0.budget)?,
-                    code_entry.code.as_slice(),
-                )?;
-                vm.invoke_function_raw(self, func, args)
+                let contract_id = id.metered_clone(&self.0.budget)?;
+                let vm = match self.take_reusable_vm(id, &wasm_hash) {
+                    // A reused instance has already been instantiated, so
+                    // only accessing it again is charged, as a memory read
+                    // of no bytes.
+                    Some(vm) => {
+                        self.charge_budget(ContractCostType::VmMemRead, Some(0))?;
+                        vm
+                    }
+                    None => {
+                        let cache = self.0.module_cache.borrow().clone();
+                        match cache {
+                            Some(cache) => Vm::new_with_cache(
+                                self,
+                                contract_id,
+                                &wasm_hash,
+                                code_entry.code.as_slice(),
+                                &cache,
+                            )?,
+                            None => Vm::new(self, contract_id, code_entry.code.as_slice())?,
+                        }
+                    }
+                };
+                self.check_contract_args(&vm, func, args)?;
+                let res = vm.invoke_function_raw(self, func, args);
+                if res.is_ok() {
+                    self.keep_reusable_vm(id, wasm_hash, vm);
+                }
+                res
             }
             #[cfg(not(feature = "vm"))]
             ScContractExecutable::WasmRef(_) => Err(self.err_general("could not dispatch")),
@@ -1071,7 +1302,7 @@ impl Host {
             if let Some(cfs) = cfs_option {
                 let frame = TestContractFrame::new(id.clone(), func.clone(), args.to_vec());
                 let panic = frame.panic.clone();
-                return self.with_frame(Frame::TestContract(frame), || {
+                let res = self.with_frame(Frame::TestContract(frame), || {
                     use std::any::Any;
                     use std::panic::AssertUnwindSafe;
                     type PanicVal = Box<dyn Any + Send>;
@@ -1137,6 +1368,10 @@ impl Host {
                         }
                     }
                 });
+                if let Err(err) = &res {
+                    self.fn_error_diagnostics(id, &func, err)?;
+                }
+                return res;
             }
         }
 
@@ -1144,7 +1379,7 @@ impl Host {
 
         match &res {
             Ok(res) => self.fn_return_diagnostics(id, &func, &res)?,
-            Err(err) => {}
+            Err(err) => self.fn_error_diagnostics(id, &func, err)?,
         }
 
         return res;
@@ -1320,6 +1555,9 @@ impl Host {
             .borrow_mut()
             .has(&code_key, self.as_budget())?
         {
+            // The code already in storage has been validated when uploaded.
+            #[cfg(feature = "vm")]
+            Vm::validate(self, args.code.as_slice())?;
             self.with_mut_storage(|storage| {
                 let data = LedgerEntryData::ContractCode(ContractCodeEntry {
                     hash: Hash(hash_bytes),
@@ -1986,6 +2224,319 @@ impl VmCallerEnv for Host {
         })
     }
 
+    // Notes on metering: the operations are charged under the `Int256*`
+    // `HostCostType`s, the operands and the result by their conversions (see
+    // `host::num`).
+    fn u128_add(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        lhs: U128Val,
+        rhs: U128Val,
+    ) -> Result<U128Val, Self::Error> {
+        self.int_binop(HostCostType::Int256AddSub, lhs, rhs, u128::checked_add)
+    }
+
+    fn u128_sub(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        lhs: U128Val,
+        rhs: U128Val,
+    ) -> Result<U128Val, Self::Error> {
+        self.int_binop(HostCostType::Int256AddSub, lhs, rhs, u128::checked_sub)
+    }
+
+    fn u128_mul(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        lhs: U128Val,
+        rhs: U128Val,
+    ) -> Result<U128Val, Self::Error> {
+        self.int_binop(HostCostType::Int256Mul, lhs, rhs, u128::checked_mul)
+    }
+
+    fn u128_div(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        lhs: U128Val,
+        rhs: U128Val,
+    ) -> Result<U128Val, Self::Error> {
+        self.int_div_op(lhs, rhs, u128::checked_div)
+    }
+
+    fn u128_rem(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        lhs: U128Val,
+        rhs: U128Val,
+    ) -> Result<U128Val, Self::Error> {
+        self.int_div_op(lhs, rhs, u128::checked_rem)
+    }
+
+    fn u128_pow(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        lhs: U128Val,
+        rhs: U32Val,
+    ) -> Result<U128Val, Self::Error> {
+        self.int_pow(lhs, rhs, u128::checked_pow)
+    }
+
+    fn u128_shl(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        lhs: U128Val,
+        rhs: U32Val,
+    ) -> Result<U128Val, Self::Error> {
+        self.int_shift(lhs, rhs, u128::checked_shl)
+    }
+
+    fn u128_shr(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        lhs: U128Val,
+        rhs: U32Val,
+    ) -> Result<U128Val, Self::Error> {
+        self.int_shift(lhs, rhs, u128::checked_shr)
+    }
+
+    fn i128_add(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        lhs: I128Val,
+        rhs: I128Val,
+    ) -> Result<I128Val, Self::Error> {
+        self.int_binop(HostCostType::Int256AddSub, lhs, rhs, i128::checked_add)
+    }
+
+    fn i128_sub(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        lhs: I128Val,
+        rhs: I128Val,
+    ) -> Result<I128Val, Self::Error> {
+        self.int_binop(HostCostType::Int256AddSub, lhs, rhs, i128::checked_sub)
+    }
+
+    fn i128_mul(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        lhs: I128Val,
+        rhs: I128Val,
+    ) -> Result<I128Val, Self::Error> {
+        self.int_binop(HostCostType::Int256Mul, lhs, rhs, i128::checked_mul)
+    }
+
+    fn i128_div(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        lhs: I128Val,
+        rhs: I128Val,
+    ) -> Result<I128Val, Self::Error> {
+        self.int_div_op(lhs, rhs, i128::checked_div)
+    }
+
+    fn i128_rem(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        lhs: I128Val,
+        rhs: I128Val,
+    ) -> Result<I128Val, Self::Error> {
+        self.int_div_op(lhs, rhs, i128::checked_rem)
+    }
+
+    fn i128_pow(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        lhs: I128Val,
+        rhs: U32Val,
+    ) -> Result<I128Val, Self::Error> {
+        self.int_pow(lhs, rhs, i128::checked_pow)
+    }
+
+    fn i128_shl(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        lhs: I128Val,
+        rhs: U32Val,
+    ) -> Result<I128Val, Self::Error> {
+        self.int_shift(lhs, rhs, i128::checked_shl)
+    }
+
+    fn i128_shr(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        lhs: I128Val,
+        rhs: U32Val,
+    ) -> Result<I128Val, Self::Error> {
+        self.int_shift(lhs, rhs, i128::checked_shr)
+    }
+
+    fn u256_add(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        lhs: U256Val,
+        rhs: U256Val,
+    ) -> Result<U256Val, Self::Error> {
+        self.int_binop(HostCostType::Int256AddSub, lhs, rhs, U256::checked_add)
+    }
+
+    fn u256_sub(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        lhs: U256Val,
+        rhs: U256Val,
+    ) -> Result<U256Val, Self::Error> {
+        self.int_binop(HostCostType::Int256AddSub, lhs, rhs, U256::checked_sub)
+    }
+
+    fn u256_mul(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        lhs: U256Val,
+        rhs: U256Val,
+    ) -> Result<U256Val, Self::Error> {
+        self.int_binop(HostCostType::Int256Mul, lhs, rhs, U256::checked_mul)
+    }
+
+    fn u256_div(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        lhs: U256Val,
+        rhs: U256Val,
+    ) -> Result<U256Val, Self::Error> {
+        self.int_div_op(lhs, rhs, U256::checked_div)
+    }
+
+    fn u256_rem(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        lhs: U256Val,
+        rhs: U256Val,
+    ) -> Result<U256Val, Self::Error> {
+        self.int_div_op(lhs, rhs, U256::checked_rem)
+    }
+
+    fn u256_pow(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        lhs: U256Val,
+        rhs: U32Val,
+    ) -> Result<U256Val, Self::Error> {
+        self.int_pow(lhs, rhs, U256::checked_pow)
+    }
+
+    fn u256_shl(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        lhs: U256Val,
+        rhs: U32Val,
+    ) -> Result<U256Val, Self::Error> {
+        self.int_shift(lhs, rhs, U256::checked_shl)
+    }
+
+    fn u256_shr(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        lhs: U256Val,
+        rhs: U32Val,
+    ) -> Result<U256Val, Self::Error> {
+        self.int_shift(lhs, rhs, U256::checked_shr)
+    }
+
+    fn i256_add(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        lhs: I256Val,
+        rhs: I256Val,
+    ) -> Result<I256Val, Self::Error> {
+        self.int_binop(HostCostType::Int256AddSub, lhs, rhs, I256::checked_add)
+    }
+
+    fn i256_sub(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        lhs: I256Val,
+        rhs: I256Val,
+    ) -> Result<I256Val, Self::Error> {
+        self.int_binop(HostCostType::Int256AddSub, lhs, rhs, I256::checked_sub)
+    }
+
+    fn i256_mul(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        lhs: I256Val,
+        rhs: I256Val,
+    ) -> Result<I256Val, Self::Error> {
+        self.int_binop(HostCostType::Int256Mul, lhs, rhs, I256::checked_mul)
+    }
+
+    fn i256_div(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        lhs: I256Val,
+        rhs: I256Val,
+    ) -> Result<I256Val, Self::Error> {
+        self.int_div_op(lhs, rhs, I256::checked_div)
+    }
+
+    fn i256_rem(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        lhs: I256Val,
+        rhs: I256Val,
+    ) -> Result<I256Val, Self::Error> {
+        self.int_div_op(lhs, rhs, I256::checked_rem)
+    }
+
+    fn i256_pow(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        lhs: I256Val,
+        rhs: U32Val,
+    ) -> Result<I256Val, Self::Error> {
+        self.int_pow(lhs, rhs, I256::checked_pow)
+    }
+
+    fn i256_shl(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        lhs: I256Val,
+        rhs: U32Val,
+    ) -> Result<I256Val, Self::Error> {
+        self.int_shift(lhs, rhs, I256::checked_shl)
+    }
+
+    fn i256_shr(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        lhs: I256Val,
+        rhs: U32Val,
+    ) -> Result<I256Val, Self::Error> {
+        self.int_shift(lhs, rhs, I256::checked_shr)
+    }
+
+    fn i128_mul_div(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        a: I128Val,
+        b: I128Val,
+        c: I128Val,
+        rounding: U32Val,
+    ) -> Result<I128Val, Self::Error> {
+        self.int_mul_div(a, b, c, rounding, i128_mul_div_costs, i128_mul_div)
+    }
+
+    fn u256_mul_div(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        a: U256Val,
+        b: U256Val,
+        c: U256Val,
+        rounding: U32Val,
+    ) -> Result<U256Val, Self::Error> {
+        self.int_mul_div(a, b, c, rounding, u256_mul_div_costs, u256_mul_div)
+    }
+
     fn map_new(&self, _vmcaller: &mut VmCaller<Host>) -> Result<MapObject, HostError> {
         self.add_host_object(HostMap::new()?)
     }
@@ -2491,16 +3042,19 @@ impl VmCallerEnv for Host {
         v: RawVal,
     ) -> Result<Void, HostError> {
         let key = self.contract_data_key_from_rawval(k)?;
+        self.storage_trace_diagnostics("put", &key);
         let data = LedgerEntryData::ContractData(ContractDataEntry {
             contract_id: self.get_current_contract_id_internal()?,
             key: self.from_host_val(k)?,
             val: self.from_host_val(v)?,
         });
+        let is_new = !self.0.storage.borrow().exists(&key, self.as_budget())?;
         self.0.storage.borrow_mut().put(
             &key,
             &Host::ledger_entry_from_data(data),
             self.as_budget(),
         )?;
+        self.maybe_init_entry_expiration(&key, is_new)?;
         Ok(RawVal::VOID)
     }
 
@@ -2511,8 +3065,9 @@ impl VmCallerEnv for Host {
         k: RawVal,
     ) -> Result<Bool, HostError> {
         let key = self.storage_key_from_rawval(k)?;
+        self.storage_trace_diagnostics("has", &key);
         let res = self.0.storage.borrow_mut().has(&key, self.as_budget())?;
-        Ok(RawVal::from_bool(res))
+        Ok(RawVal::from_bool(res && !self.is_entry_expired(&key)?))
     }
 
     // Notes on metering: covered by components
@@ -2522,7 +3077,8 @@ impl VmCallerEnv for Host {
         k: RawVal,
     ) -> Result<RawVal, HostError> {
         let key = self.storage_key_from_rawval(k)?;
-        let entry = self.0.storage.borrow_mut().get(&key, self.as_budget())?;
+        self.storage_trace_diagnostics("get", &key);
+        let entry = self.get_live_contract_data_entry(&key)?;
         match &entry.data {
             LedgerEntryData::ContractData(ContractDataEntry {
                 contract_id,
@@ -2543,7 +3099,116 @@ impl VmCallerEnv for Host {
         k: RawVal,
     ) -> Result<Void, HostError> {
         let key = self.contract_data_key_from_rawval(k)?;
-        self.0.storage.borrow_mut().del(&key, self.as_budget())?;
+        self.storage_trace_diagnostics("del", &key);
+        let mut storage = self.0.storage.borrow_mut();
+        storage.del(&key, self.as_budget())?;
+        storage.clear_expiration(&key, self.as_budget())?;
+        Ok(RawVal::VOID)
+    }
+
+    // Notes on metering: covered by components
+    fn bump_contract_data(
+        &self,
+        _vmcaller: &mut VmCaller<Host>,
+        k: RawVal,
+        ledgers_to_live: U32Val,
+    ) -> Result<Void, HostError> {
+        let key = self.contract_data_key_from_rawval(k)?;
+        self.storage_trace_diagnostics("bump", &key);
+        if !self.0.storage.borrow_mut().has(&key, self.as_budget())? {
+            return Err(self.err_status_msg(
+                ScHostStorageErrorCode::MissingKeyInGet,
+                "trying to bump non-existing contract data entry",
+            ));
+        }
+        self.check_entry_not_expired(&key)?;
+        let expiration = self.expiration_ledger_from_ledgers_to_live(ledgers_to_live.into())?;
+        self.0
+            .storage
+            .borrow_mut()
+            .bump(&key, expiration, self.as_budget())?;
+        Ok(RawVal::VOID)
+    }
+
+    // Notes on metering: covered by components
+    fn put_expiring_contract_data(
+        &self,
+        _vmcaller: &mut VmCaller<Host>,
+        k: RawVal,
+        v: RawVal,
+    ) -> Result<Void, HostError> {
+        let key_scval = self.expiring_contract_data_key_scval_from_rawval(k)?;
+        let key = self.storage_key_from_scval(key_scval.metered_clone(self.as_budget())?)?;
+        self.storage_trace_diagnostics("put", &key);
+        let data = LedgerEntryData::ContractData(ContractDataEntry {
+            contract_id: self.get_current_contract_id_internal()?,
+            key: key_scval,
+            val: self.from_host_val(v)?,
+        });
+        // Expired entries are gone for good, so writing to one
+        // creates a brand new entry.
+        let is_new = if self.is_entry_expired(&key)? {
+            self.0
+                .storage
+                .borrow_mut()
+                .clear_expiration(&key, self.as_budget())?;
+            true
+        } else {
+            !self.0.storage.borrow().exists(&key, self.as_budget())?
+        };
+        self.0.storage.borrow_mut().put(
+            &key,
+            &Host::ledger_entry_from_data(data),
+            self.as_budget(),
+        )?;
+        let min_expiration = self.with_ledger_info(|li| Ok(li.min_temp_entry_expiration))?;
+        self.init_entry_expiration(&key, min_expiration, is_new)?;
+        Ok(RawVal::VOID)
+    }
+
+    // Notes on metering: covered by components
+    fn has_expiring_contract_data(
+        &self,
+        _vmcaller: &mut VmCaller<Host>,
+        k: RawVal,
+    ) -> Result<Bool, HostError> {
+        let key = self.expiring_contract_data_key_from_rawval(k)?;
+        self.storage_trace_diagnostics("has", &key);
+        let res = self.0.storage.borrow_mut().has(&key, self.as_budget())?;
+        Ok(RawVal::from_bool(res && !self.is_entry_expired(&key)?))
+    }
+
+    // Notes on metering: covered by components
+    fn get_expiring_contract_data(
+        &self,
+        _vmcaller: &mut VmCaller<Host>,
+        k: RawVal,
+    ) -> Result<RawVal, HostError> {
+        let key = self.expiring_contract_data_key_from_rawval(k)?;
+        self.storage_trace_diagnostics("get", &key);
+        let entry = self.get_live_contract_data_entry(&key)?;
+        match &entry.data {
+            LedgerEntryData::ContractData(ContractDataEntry { val, .. }) => {
+                Ok(self.to_host_val(&val)?.into())
+            }
+            _ => Err(self.err_status_msg(
+                ScHostStorageErrorCode::ExpectContractData,
+                "expected contract data",
+            )),
+        }
+    }
+
+    // Notes on metering: covered by components
+    fn del_expiring_contract_data(
+        &self,
+        _vmcaller: &mut VmCaller<Host>,
+        k: RawVal,
+    ) -> Result<Void, HostError> {
+        let key = self.expiring_contract_data_key_from_rawval(k)?;
+        self.storage_trace_diagnostics("del", &key);
+        let mut storage = self.0.storage.borrow_mut();
+        storage.del(&key, self.as_budget())?;
+        storage.clear_expiration(&key, self.as_budget())?;
         Ok(RawVal::VOID)
     }
 
@@ -3116,6 +3781,81 @@ impl VmCallerEnv for Host {
         Ok(res?.into())
     }
 
+    fn verify_sig_ecdsa_secp256k1(
+        &self,
+        _vmcaller: &mut VmCaller<Host>,
+        public_key: BytesObject,
+        msg_digest: BytesObject,
+        signature: BytesObject,
+    ) -> Result<Void, HostError> {
+        let public_key = self.secp256k1_pub_key_from_bytesobj_input(public_key)?;
+        let digest = self.ecdsa_digest_from_bytesobj_input(msg_digest)?;
+        let sig = self.secp256k1_signature_from_bytesobj_input(signature)?;
+        self.verify_sig_ecdsa_secp256k1_internal(&digest, &public_key, &sig)?;
+        Ok(RawVal::VOID)
+    }
+
+    fn recover_key_ecdsa_secp256k1(
+        &self,
+        _vmcaller: &mut VmCaller<Host>,
+        msg_digest: BytesObject,
+        signature: BytesObject,
+        recovery_id: U32Val,
+    ) -> Result<BytesObject, HostError> {
+        let digest = self.ecdsa_digest_from_bytesobj_input(msg_digest)?;
+        let sig = self.secp256k1_signature_from_bytesobj_input(signature)?;
+        let recovery_id = self.secp256k1_recovery_id_from_u32val(recovery_id)?;
+        let key = self.recover_key_ecdsa_secp256k1_internal(&digest, &sig, recovery_id)?;
+        self.add_host_object(key)
+    }
+
+    fn verify_sig_ecdsa_secp256r1(
+        &self,
+        _vmcaller: &mut VmCaller<Host>,
+        public_key: BytesObject,
+        msg_digest: BytesObject,
+        signature: BytesObject,
+    ) -> Result<Void, HostError> {
+        let public_key = self.secp256r1_pub_key_from_bytesobj_input(public_key)?;
+        let digest = self.ecdsa_digest_from_bytesobj_input(msg_digest)?;
+        let sig = self.secp256r1_signature_from_bytesobj_input(signature)?;
+        self.verify_sig_ecdsa_secp256r1_internal(&digest, &public_key, &sig)?;
+        Ok(RawVal::VOID)
+    }
+
+    fn compute_hash_keccak256(
+        &self,
+        _vmcaller: &mut VmCaller<Host>,
+        x: BytesObject,
+    ) -> Result<BytesObject, HostError> {
+        let hash = self.visit_obj(x, |bytes: &ScBytes| {
+            self.keccak256_hash_from_bytes(bytes.as_slice())
+        })?;
+        self.add_host_object(self.scbytes_from_vec(hash)?)
+    }
+
+    fn compute_hash_sha512(
+        &self,
+        _vmcaller: &mut VmCaller<Host>,
+        x: BytesObject,
+    ) -> Result<BytesObject, HostError> {
+        let hash = self.visit_obj(x, |bytes: &ScBytes| {
+            self.sha512_hash_from_bytes(bytes.as_slice())
+        })?;
+        self.add_host_object(self.scbytes_from_vec(hash)?)
+    }
+
+    fn compute_hash_blake2b(
+        &self,
+        _vmcaller: &mut VmCaller<Host>,
+        x: BytesObject,
+    ) -> Result<BytesObject, HostError> {
+        let hash = self.visit_obj(x, |bytes: &ScBytes| {
+            self.blake2b_hash_from_bytes(bytes.as_slice())
+        })?;
+        self.add_host_object(self.scbytes_from_vec(hash)?)
+    }
+
     fn get_ledger_version(&self, _vmcaller: &mut VmCaller<Host>) -> Result<U32Val, Self::Error> {
         self.with_ledger_info(|li| Ok(li.protocol_version.into()))
     }
@@ -3196,17 +3936,14 @@ impl VmCallerEnv for Host {
     ) -> Result<RawVal, Self::Error> {
         let addr = self.visit_obj(address, |addr: &ScAddress| Ok(addr.clone()))?;
 
-        Ok(self
-            .0
-            .authorization_manager
-            .borrow_mut()
-            .require_auth(
-                self,
-                address.get_handle(),
-                addr,
-                self.call_args_to_scvec(args)?,
-            )?
-            .into())
+        let res = self.0.authorization_manager.borrow_mut().require_auth(
+            self,
+            address.get_handle(),
+            addr,
+            self.call_args_to_scvec(args)?,
+        );
+        self.auth_trace_diagnostics(address, &res);
+        Ok(res?.into())
     }
 
     fn require_auth(
@@ -3229,12 +3966,14 @@ impl VmCallerEnv for Host {
             Ok(self.rawvals_to_scvec(args.iter())?)
         })?;
 
-        Ok(self
-            .0
-            .authorization_manager
-            .borrow_mut()
-            .require_auth(self, address.get_handle(), addr, args)?
-            .into())
+        let res = self.0.authorization_manager.borrow_mut().require_auth(
+            self,
+            address.get_handle(),
+            addr,
+            args,
+        );
+        self.auth_trace_diagnostics(address, &res);
+        Ok(res?.into())
     }
 
     fn get_current_contract_id(
@@ -3290,6 +4029,21 @@ impl VmCallerEnv for Host {
                 .into()),
         }
     }
+
+    fn authorize_as_curr_contract(
+        &self,
+        _vmcaller: &mut VmCaller<Self::VmUserState>,
+        auth_entries: VecObject,
+    ) -> Result<Void, Self::Error> {
+        use crate::native_contract::invoker_contract_auth::invoker_contract_auth_to_authorized_invocations;
+
+        let auth_entries = invoker_contract_auth_to_authorized_invocations(self, auth_entries)?;
+        self.0
+            .authorization_manager
+            .borrow_mut()
+            .add_invoker_contract_auth(self, auth_entries)?;
+        Ok(RawVal::VOID.into())
+    }
 }
 
 #[cfg(any(test, feature = "testutils"))]
@@ -2,6 +2,7 @@ use std::rc::Rc;
 
 use super::metered_clone::MeteredClone;
 use crate::host_object::HostVec;
+use crate::storage::expiring_key_scval;
 use crate::xdr::{
     Hash, LedgerKey, LedgerKeyContractData, ScHostFnErrorCode, ScHostObjErrorCode,
     ScHostValErrorCode, ScVal, ScVec, Uint256,
@@ -135,7 +136,7 @@ impl Host {
         }
     }
 
-    fn fixed_length_bytes_from_bytesobj_input<T, const N: usize>(
+    pub(crate) fn fixed_length_bytes_from_bytesobj_input<T, const N: usize>(
         &self,
         name: &'static str,
         obj: BytesObject,
@@ -217,6 +218,31 @@ impl Host {
 
     // Notes on metering: covered by components.
     pub fn contract_data_key_from_rawval(&self, k: RawVal) -> Result<Rc<LedgerKey>, HostError> {
+        let key_scval = self.contract_data_key_scval_from_rawval(k)?;
+        self.storage_key_from_scval(key_scval)
+    }
+
+    /// Same as [`Host::contract_data_key_from_rawval`], but produces the key
+    /// of an expiring contract data entry (see
+    /// [`expiring_key_scval`](crate::storage::expiring_key_scval)).
+    // Notes on metering: covered by components.
+    pub fn expiring_contract_data_key_from_rawval(
+        &self,
+        k: RawVal,
+    ) -> Result<Rc<LedgerKey>, HostError> {
+        let key_scval = self.expiring_contract_data_key_scval_from_rawval(k)?;
+        self.storage_key_from_scval(key_scval)
+    }
+
+    pub(crate) fn expiring_contract_data_key_scval_from_rawval(
+        &self,
+        k: RawVal,
+    ) -> Result<ScVal, HostError> {
+        let key_scval = self.contract_data_key_scval_from_rawval(k)?;
+        expiring_key_scval(self.get_current_contract_id_internal()?, key_scval)
+    }
+
+    fn contract_data_key_scval_from_rawval(&self, k: RawVal) -> Result<ScVal, HostError> {
         let key_scval = self.from_host_val(k)?;
         match &key_scval {
             ScVal::LedgerKeyContractExecutable => {
@@ -233,7 +259,7 @@ impl Host {
             }
             _ => (),
         };
-        self.storage_key_from_scval(key_scval)
+        Ok(key_scval)
     }
 
     /// Converts a binary search result into a u64. `res` is `Some(index)`
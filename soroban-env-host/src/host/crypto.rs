@@ -0,0 +1,200 @@
+use blake2::{
+    digest::{Update, VariableOutput},
+    VarBlake2b,
+};
+use k256::ecdsa::{
+    signature::hazmat::PrehashVerifier, RecoveryId, Signature as Secp256k1Signature,
+    VerifyingKey as Secp256k1VerifyingKey,
+};
+use p256::ecdsa::{Signature as Secp256r1Signature, VerifyingKey as Secp256r1VerifyingKey};
+use sha2::{Digest, Sha512};
+use sha3::Keccak256;
+
+use crate::xdr::{ScBytes, ScHostFnErrorCode, ScHostObjErrorCode};
+use crate::{budget::HostCostType, BytesObject, Host, HostError, U32Val};
+
+// The ECDSA functions operate on the digest of the message, computed by the
+// caller with the hash function of its choice (e.g. keccak256 for the
+// Ethereum signatures).
+pub(crate) const ECDSA_DIGEST_LENGTH: usize = 32;
+
+pub(crate) const BLAKE2B_HASH_LENGTH: usize = 32;
+
+impl Host {
+    // Accepts both the compressed (33 bytes) and the uncompressed (65 bytes)
+    // SEC1 encodings.
+    pub(crate) fn secp256k1_pub_key_from_bytes(
+        &self,
+        bytes: &[u8],
+    ) -> Result<Secp256k1VerifyingKey, HostError> {
+        self.charge_budget(HostCostType::ComputeSecp256k1PubKey, None)?;
+        Secp256k1VerifyingKey::from_sec1_bytes(bytes).map_err(|_| {
+            self.err_status_msg(
+                ScHostObjErrorCode::UnexpectedType,
+                "invalid secp256k1 public key",
+            )
+        })
+    }
+
+    pub(crate) fn secp256k1_pub_key_from_bytesobj_input(
+        &self,
+        k: BytesObject,
+    ) -> Result<Secp256k1VerifyingKey, HostError> {
+        self.visit_obj(k, |bytes: &ScBytes| {
+            self.secp256k1_pub_key_from_bytes(bytes.as_slice())
+        })
+    }
+
+    // The signature is the 64 bytes concatenation of the big-endian `r` and
+    // `s`.
+    pub(crate) fn secp256k1_signature_from_bytesobj_input(
+        &self,
+        sig: BytesObject,
+    ) -> Result<Secp256k1Signature, HostError> {
+        self.visit_obj(sig, |bytes: &ScBytes| {
+            Secp256k1Signature::from_slice(bytes.as_slice()).map_err(|_| {
+                self.err_status_msg(
+                    ScHostFnErrorCode::InputArgsInvalid,
+                    "invalid secp256k1 signature",
+                )
+            })
+        })
+    }
+
+    pub(crate) fn ecdsa_digest_from_bytesobj_input(
+        &self,
+        digest: BytesObject,
+    ) -> Result<[u8; ECDSA_DIGEST_LENGTH], HostError> {
+        self.fixed_length_bytes_from_bytesobj_input::<_, ECDSA_DIGEST_LENGTH>("digest", digest)
+    }
+
+    pub(crate) fn secp256k1_recovery_id_from_u32val(
+        &self,
+        recovery_id: U32Val,
+    ) -> Result<RecoveryId, HostError> {
+        u8::try_from(u32::from(recovery_id))
+            .ok()
+            .and_then(RecoveryId::from_byte)
+            .ok_or_else(|| {
+                self.err_status_msg_with_args(
+                    ScHostFnErrorCode::InputArgsInvalid,
+                    "invalid secp256k1 recovery id",
+                    &[recovery_id.into()],
+                )
+            })
+    }
+
+    // Notes on metering: the XDR has no cost type for secp256k1, so this and
+    // the recovery below are charged under cost types of the host, whose
+    // models are configured past the XDR ones (see `HostCostType`). The
+    // digest being of fixed length, their cost is constant.
+    pub(crate) fn verify_sig_ecdsa_secp256k1_internal(
+        &self,
+        digest: &[u8; ECDSA_DIGEST_LENGTH],
+        public_key: &Secp256k1VerifyingKey,
+        sig: &Secp256k1Signature,
+    ) -> Result<(), HostError> {
+        self.charge_budget(HostCostType::VerifyEcdsaSecp256k1Sig, None)?;
+        public_key
+            .verify_prehash(digest, sig)
+            .map_err(|_| self.err_general("Failed secp256k1 verification"))
+    }
+
+    // Returns the uncompressed SEC1 encoding (65 bytes) of the recovered key.
+    pub(crate) fn recover_key_ecdsa_secp256k1_internal(
+        &self,
+        digest: &[u8; ECDSA_DIGEST_LENGTH],
+        sig: &Secp256k1Signature,
+        recovery_id: RecoveryId,
+    ) -> Result<ScBytes, HostError> {
+        self.charge_budget(HostCostType::RecoverEcdsaSecp256k1Key, None)?;
+        let key = Secp256k1VerifyingKey::recover_from_prehash(digest, sig, recovery_id)
+            .map_err(|_| self.err_general("Failed secp256k1 key recovery"))?;
+        self.scbytes_from_slice(key.to_encoded_point(false).as_bytes())
+    }
+
+    // Same encodings as for secp256k1.
+    pub(crate) fn secp256r1_pub_key_from_bytes(
+        &self,
+        bytes: &[u8],
+    ) -> Result<Secp256r1VerifyingKey, HostError> {
+        self.charge_budget(HostCostType::ComputeSecp256r1PubKey, None)?;
+        Secp256r1VerifyingKey::from_sec1_bytes(bytes).map_err(|_| {
+            self.err_status_msg(
+                ScHostObjErrorCode::UnexpectedType,
+                "invalid secp256r1 public key",
+            )
+        })
+    }
+
+    pub(crate) fn secp256r1_pub_key_from_bytesobj_input(
+        &self,
+        k: BytesObject,
+    ) -> Result<Secp256r1VerifyingKey, HostError> {
+        self.visit_obj(k, |bytes: &ScBytes| {
+            self.secp256r1_pub_key_from_bytes(bytes.as_slice())
+        })
+    }
+
+    // Same encoding as for secp256k1. Only the signatures with `s` in the
+    // lower half of the curve order are accepted, as both `s` and `-s` make
+    // a valid signature and the callers mustn't be able to produce a second
+    // signature out of a first one.
+    pub(crate) fn secp256r1_signature_from_bytesobj_input(
+        &self,
+        sig: BytesObject,
+    ) -> Result<Secp256r1Signature, HostError> {
+        self.visit_obj(sig, |bytes: &ScBytes| {
+            let sig = Secp256r1Signature::from_slice(bytes.as_slice()).map_err(|_| {
+                self.err_status_msg(
+                    ScHostFnErrorCode::InputArgsInvalid,
+                    "invalid secp256r1 signature",
+                )
+            })?;
+            if sig.normalize_s().is_some() {
+                return Err(self.err_status_msg(
+                    ScHostFnErrorCode::InputArgsInvalid,
+                    "secp256r1 signature 's' part is not normalized to low form",
+                ));
+            }
+            Ok(sig)
+        })
+    }
+
+    // Notes on metering: charged under a cost type of the host, as with
+    // secp256k1.
+    pub(crate) fn verify_sig_ecdsa_secp256r1_internal(
+        &self,
+        digest: &[u8; ECDSA_DIGEST_LENGTH],
+        public_key: &Secp256r1VerifyingKey,
+        sig: &Secp256r1Signature,
+    ) -> Result<(), HostError> {
+        self.charge_budget(HostCostType::VerifyEcdsaSecp256r1Sig, None)?;
+        public_key
+            .verify_prehash(digest, sig)
+            .map_err(|_| self.err_general("Failed secp256r1 verification"))
+    }
+
+    // Notes on metering: the XDR has no cost types for these hashes, so they
+    // are charged under cost types of the host (configured past the XDR ones,
+    // see `HostCostType`), linear in the length of the input like sha256.
+    pub(crate) fn keccak256_hash_from_bytes(&self, bytes: &[u8]) -> Result<Vec<u8>, HostError> {
+        self.charge_budget(HostCostType::ComputeKeccak256Hash, Some(bytes.len() as u64))?;
+        Ok(Keccak256::digest(bytes).as_slice().to_vec())
+    }
+
+    pub(crate) fn sha512_hash_from_bytes(&self, bytes: &[u8]) -> Result<Vec<u8>, HostError> {
+        self.charge_budget(HostCostType::ComputeSha512Hash, Some(bytes.len() as u64))?;
+        Ok(Sha512::digest(bytes).as_slice().to_vec())
+    }
+
+    pub(crate) fn blake2b_hash_from_bytes(&self, bytes: &[u8]) -> Result<Vec<u8>, HostError> {
+        self.charge_budget(HostCostType::ComputeBlake2bHash, Some(bytes.len() as u64))?;
+        let mut hasher = VarBlake2b::new(BLAKE2B_HASH_LENGTH)
+            .map_err(|_| self.err_general("invalid blake2b output size"))?;
+        hasher.update(bytes);
+        let mut hash = vec![0; BLAKE2B_HASH_LENGTH];
+        hasher.finalize_variable(|res| hash.copy_from_slice(res));
+        Ok(hash)
+    }
+}
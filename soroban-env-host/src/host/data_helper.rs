@@ -9,7 +9,7 @@ use crate::xdr::{
     HashIdPreimageContractId, HashIdPreimageCreateContractArgs, HashIdPreimageEd25519ContractId,
     HashIdPreimageFromAsset, HashIdPreimageSourceAccountContractId, LedgerEntry, LedgerEntryData,
     LedgerEntryExt, LedgerKey, LedgerKeyAccount, LedgerKeyContractCode, LedgerKeyContractData,
-    LedgerKeyTrustLine, PublicKey, ScContractExecutable, ScHostStorageErrorCode,
+    LedgerKeyTrustLine, PublicKey, ScContractExecutable, ScHostFnErrorCode, ScHostStorageErrorCode,
     ScHostValErrorCode, ScVal, Signer, SignerKey, ThresholdIndexes, TrustLineAsset, Uint256,
 };
 use crate::{Host, HostError};
@@ -258,6 +258,130 @@ impl Host {
         }
     }
 
+    /// Fails with [ScHostStorageErrorCode::GetOnDeletedKey] if the entry
+    /// under `key` has an expiration ledger that is earlier than the current
+    /// ledger. Entries without expiration are always live.
+    ///
+    /// The XDR has no error code dedicated to the expired entries, so they
+    /// are reported as deleted ones. The contract data functions report the
+    /// entries missing from the ledger as
+    /// [ScHostStorageErrorCode::MissingKeyInGet] instead (see
+    /// [Host::get_live_contract_data_entry]), which leaves `GetOnDeletedKey`
+    /// to the expired entries alone.
+    pub(crate) fn check_entry_not_expired(&self, key: &Rc<LedgerKey>) -> Result<(), HostError> {
+        let expiration = self
+            .0
+            .storage
+            .borrow_mut()
+            .get_expiration(key, self.as_budget())?;
+        if let Some(expiration) = expiration {
+            let ledger_seq = self.with_ledger_info(|li| Ok(li.sequence_number))?;
+            if expiration < ledger_seq {
+                return Err(self.err_status_msg_with_args(
+                    ScHostStorageErrorCode::GetOnDeletedKey,
+                    "accessing entry that has expired at ledger {}",
+                    &[expiration.into()],
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the contract data entry under `key`, failing if there is no
+    /// such entry or if it has expired (see [Host::check_entry_not_expired]).
+    pub(crate) fn get_live_contract_data_entry(
+        &self,
+        key: &Rc<LedgerKey>,
+    ) -> Result<Rc<LedgerEntry>, HostError> {
+        let res = self.0.storage.borrow_mut().get(key, self.as_budget());
+        let entry = match res {
+            // The storage reports the entries missing from the ledger as
+            // deleted, which is reserved for the expired entries here.
+            Err(e) if e.status == ScHostStorageErrorCode::GetOnDeletedKey.into() => {
+                return Err(self.err_status_msg(
+                    ScHostStorageErrorCode::MissingKeyInGet,
+                    "trying to get non-existing contract data entry",
+                ));
+            }
+            res => res?,
+        };
+        self.check_entry_not_expired(key)?;
+        Ok(entry)
+    }
+
+    /// Returns `true` if the entry under `key` has an expiration ledger that
+    /// is earlier than the current ledger.
+    pub(crate) fn is_entry_expired(&self, key: &Rc<LedgerKey>) -> Result<bool, HostError> {
+        let ledger_seq = self.with_ledger_info(|li| Ok(li.sequence_number))?;
+        self.0
+            .storage
+            .borrow_mut()
+            .is_expired(key, ledger_seq, self.as_budget())
+    }
+
+    /// Assigns the initial expiration ledger to the entry under `key` (see
+    /// [Host::init_entry_expiration]) if the network configures entries to
+    /// expire at all.
+    pub(crate) fn maybe_init_entry_expiration(
+        &self,
+        key: &Rc<LedgerKey>,
+        is_new: bool,
+    ) -> Result<(), HostError> {
+        let min_expiration = match self.0.ledger.borrow().as_ref() {
+            Some(li) => li.min_persistent_entry_expiration,
+            None => return Ok(()),
+        };
+        if min_expiration == 0 {
+            return Ok(());
+        }
+        self.init_entry_expiration(key, min_expiration, is_new)
+    }
+
+    /// Assigns the expiration ledger `ledgers_to_live` ledgers after the
+    /// current one to the entry under `key`, which has just been written and
+    /// was created by that write if `is_new`.
+    ///
+    /// Only the new entries and the ones that have expired get an expiration
+    /// ledger: the live ones keep theirs, and the existing entries without
+    /// one never expire.
+    // Notes on metering: covered by the storage calls.
+    pub(crate) fn init_entry_expiration(
+        &self,
+        key: &Rc<LedgerKey>,
+        ledgers_to_live: u32,
+        is_new: bool,
+    ) -> Result<(), HostError> {
+        let ledger_seq = self.with_ledger_info(|li| Ok(li.sequence_number))?;
+        let mut storage = self.0.storage.borrow_mut();
+        match storage.get_expiration(key, self.as_budget())? {
+            Some(expiration) if expiration >= ledger_seq => Ok(()),
+            None if !is_new => Ok(()),
+            Some(_) | None => storage.set_expiration(
+                key,
+                ledger_seq.saturating_add(ledgers_to_live),
+                self.as_budget(),
+            ),
+        }
+    }
+
+    /// Converts a relative number of ledgers into an absolute expiration
+    /// ledger, enforcing the network's maximum entry lifetime.
+    pub(crate) fn expiration_ledger_from_ledgers_to_live(
+        &self,
+        ledgers_to_live: u32,
+    ) -> Result<u32, HostError> {
+        let (ledger_seq, max_expiration) =
+            self.with_ledger_info(|li| Ok((li.sequence_number, li.max_entry_expiration)))?;
+        if max_expiration != 0 && ledgers_to_live > max_expiration {
+            return Err(self.err_status_msg_with_args(
+                ScHostFnErrorCode::InputArgsInvalid,
+                "ledgers_to_live exceeds the maximum entry expiration of {}",
+                &[max_expiration.into()],
+            ));
+        }
+        Ok(ledger_seq.saturating_add(ledgers_to_live))
+    }
+
     pub(crate) fn ledger_entry_from_data(data: LedgerEntryData) -> Rc<LedgerEntry> {
         Rc::new(LedgerEntry {
             // This is modified to the appropriate value on the core side during
@@ -1,9 +1,14 @@
+use std::rc::Rc;
+
 use soroban_env_common::{
-    xdr::{ContractEventType, Hash, ScBytes},
-    BytesObject, EnvBase, Symbol, SymbolSmall, VecObject,
+    xdr::{ContractEventType, Hash, LedgerKey, LedgerKeyContractData, ScBytes},
+    AddressObject, BytesObject, EnvBase, Symbol, SymbolSmall, TryFromVal, TryIntoVal, VecObject,
 };
 
-use crate::{budget::AsBudget, Host, HostError, RawVal};
+use crate::{
+    budget::{AsBudget, BudgetExceeded, CostType, ExceededFrame},
+    Host, HostError, RawVal, Status, SymbolStr,
+};
 use crate::{
     events::{InternalContractEvent, InternalEvent},
     host_object::HostVec,
@@ -108,4 +113,227 @@ impl Host {
             )
         })
     }
+
+    // Emits an event with topic = ["fn_error", function_name] and
+    // data = [status] for a call that has failed. Only emitted when tracing,
+    // so that the events recorded at the debug level are left unchanged.
+    pub(crate) fn fn_error_diagnostics(
+        &self,
+        contract_id: &Hash,
+        func: &Symbol,
+        err: &HostError,
+    ) -> Result<(), HostError> {
+        if !self.is_trace() {
+            return Ok(());
+        }
+
+        self.as_budget().with_free_budget(|| {
+            let topics: Vec<RawVal> =
+                vec![SymbolSmall::try_from_str("fn_error")?.into(), func.into()];
+
+            self.record_system_debug_contract_event(
+                ContractEventType::Diagnostic,
+                Some(self.hash_to_bytesobj(contract_id)?),
+                self.add_host_object(HostVec::from_vec(topics)?)?
+                    .try_into()?,
+                err.status.to_raw(),
+            )
+        })
+    }
+
+    // Returns the budget counts to compute the cost of a host function call
+    // from, if the host function calls are traced.
+    pub(crate) fn host_fn_trace_start(&self) -> Option<(u64, u64)> {
+        if !self.is_trace() {
+            return None;
+        }
+        let budget = self.as_budget();
+        Some((budget.get_cpu_insns_count(), budget.get_mem_bytes_count()))
+    }
+
+    // Emits an event with topics = ["host_fn", module, function_name] and
+    // data = [[arg1, arg2, ...], result, cpu_insns, mem_bytes], where result
+    // is the error status if the call has failed and the costs are the ones
+    // charged since `start`. Like the other trace diagnostics, failing to
+    // record the event doesn't affect the traced call.
+    pub(crate) fn host_fn_trace_diagnostics(
+        &self,
+        module: &str,
+        func: &str,
+        start: (u64, u64),
+        args: impl FnOnce() -> Result<Vec<RawVal>, HostError>,
+        res: impl FnOnce() -> Result<RawVal, HostError>,
+    ) {
+        let budget = self.as_budget();
+        let cpu_insns = budget.get_cpu_insns_count().saturating_sub(start.0);
+        let mem_bytes = budget.get_mem_bytes_count().saturating_sub(start.1);
+        let _ = budget.with_free_budget(|| {
+            let contract_id = match self.get_current_contract_id()? {
+                Some(id) => Some(self.hash_to_bytesobj(&id)?),
+                None => None,
+            };
+            let topics: Vec<RawVal> = vec![
+                SymbolSmall::try_from_str("host_fn")?.into(),
+                Symbol::try_from_val(self, &module)?.into(),
+                Symbol::try_from_val(self, &func)?.into(),
+            ];
+            let data: Vec<RawVal> = vec![
+                self.vec_new_from_slice(&args()?)?.into(),
+                res()?,
+                cpu_insns.try_into_val(self)?,
+                mem_bytes.try_into_val(self)?,
+            ];
+            self.record_system_debug_contract_event(
+                ContractEventType::Diagnostic,
+                contract_id,
+                self.add_host_object(HostVec::from_vec(topics)?)?
+                    .try_into()?,
+                self.vec_new_from_slice(&data)?.into(),
+            )
+        });
+    }
+
+    // Emits an event with topics = ["storage", access] and data = [key] for
+    // an access to the contract data entry under `key`.
+    pub(crate) fn storage_trace_diagnostics(&self, access: &str, key: &Rc<LedgerKey>) {
+        if !self.is_trace() {
+            return;
+        }
+        let LedgerKey::ContractData(LedgerKeyContractData { contract_id, key }) = key.as_ref()
+        else {
+            return;
+        };
+        let _ = self.as_budget().with_free_budget(|| {
+            let topics: Vec<RawVal> = vec![
+                SymbolSmall::try_from_str("storage")?.into(),
+                Symbol::try_from_val(self, &access)?.into(),
+            ];
+            self.record_system_debug_contract_event(
+                ContractEventType::Diagnostic,
+                Some(self.hash_to_bytesobj(contract_id)?),
+                self.add_host_object(HostVec::from_vec(topics)?)?
+                    .try_into()?,
+                self.vec_new_from_slice(&[self.to_host_val(key)?])?.into(),
+            )
+        });
+    }
+
+    // Emits an event with topics = ["auth", address] and data = [result] for
+    // a `require_auth` check, where result is `true` for the successful
+    // checks and the error status otherwise.
+    pub(crate) fn auth_trace_diagnostics(
+        &self,
+        address: AddressObject,
+        res: &Result<(), HostError>,
+    ) {
+        if !self.is_trace() {
+            return;
+        }
+        let _ = self.as_budget().with_free_budget(|| {
+            let contract_id = match self.get_current_contract_id()? {
+                Some(id) => Some(self.hash_to_bytesobj(&id)?),
+                None => None,
+            };
+            let topics: Vec<RawVal> =
+                vec![SymbolSmall::try_from_str("auth")?.into(), address.into()];
+            let outcome = match res {
+                Ok(()) => RawVal::from_bool(true).into(),
+                Err(e) => e.status.to_raw(),
+            };
+            self.record_system_debug_contract_event(
+                ContractEventType::Diagnostic,
+                contract_id,
+                self.add_host_object(HostVec::from_vec(topics)?)?
+                    .try_into()?,
+                outcome,
+            )
+        });
+    }
+
+    // Returns the id of the contract a frame belongs to (if any) and the name
+    // of the invoked function, for diagnostic purposes. This isn't metered, so
+    // it must be called with free budget.
+    pub(crate) fn describe_frame(
+        &self,
+        frame: &Frame,
+    ) -> Result<(Option<Hash>, String), HostError> {
+        let (contract_id, function) = match frame {
+            #[cfg(feature = "vm")]
+            Frame::ContractVM(vm, func, _) => (Some(vm.contract_id.clone()), *func),
+            Frame::HostFunction(hf) => return Ok((None, format!("{:?}", hf))),
+            Frame::Token(id, func, _) => (Some(id.clone()), *func),
+            #[cfg(any(test, feature = "testutils"))]
+            Frame::TestContract(tc) => (Some(tc.id.clone()), tc.func),
+        };
+        let function = SymbolStr::try_from_val(self, &function)?.to_string();
+        Ok((contract_id, function))
+    }
+
+    // Attributes the budget exhaustion that caused `err` (if any) to the
+    // current frame, which is the innermost one the error unwinds through,
+    // and attaches the budget diagnostics to the error. The first frame to
+    // see the error also emits an event with topics = ["budget_exceeded",
+    // cost_type] and data = [limit, total_count, {cost_type: count, ...}].
+    // Failing to collect the diagnostics doesn't affect the error itself.
+    pub(crate) fn attach_budget_exceeded(&self, mut err: HostError) -> HostError {
+        let budget = self.as_budget();
+        match budget.get_exceeded() {
+            Some(exceeded) if err.status == Status::from(exceeded.trapcode) => (),
+            _ => return err,
+        }
+        let _ = budget.with_free_budget(|| {
+            let frame = self.with_current_frame_opt(|frame| match frame {
+                Some(frame) => Ok(Some(self.describe_frame(frame)?)),
+                None => Ok(None),
+            })?;
+            if let Some((contract_id, function)) = frame {
+                let frame = ExceededFrame {
+                    contract_id,
+                    function,
+                };
+                if budget.set_exceeded_frame(frame) && self.is_debug() {
+                    if let Some(exceeded) = budget.get_exceeded() {
+                        self.budget_exceeded_diagnostics(&exceeded)?;
+                    }
+                }
+            }
+            Ok(())
+        });
+        err.budget_exceeded = budget.get_exceeded().map(Box::new);
+        err
+    }
+
+    fn budget_exceeded_diagnostics(&self, exceeded: &BudgetExceeded) -> Result<(), HostError> {
+        let contract_id = match exceeded.frame.as_ref().and_then(|f| f.contract_id.as_ref()) {
+            Some(id) => Some(self.hash_to_bytesobj(id)?),
+            None => None,
+        };
+        let cost_type_name = |ct: CostType| format!("{:?}", ct);
+        let topics: Vec<RawVal> = vec![
+            SymbolSmall::try_from_str("budget_exceeded")?.into(),
+            Symbol::try_from_val(self, &cost_type_name(exceeded.cost_type).as_str())?.into(),
+        ];
+        let mut names = vec![];
+        let mut counts: Vec<RawVal> = vec![];
+        for ct in CostType::variants() {
+            let count = exceeded.counts[ct.index()];
+            if count != 0 {
+                names.push(cost_type_name(ct));
+                counts.push(count.try_into_val(self)?);
+            }
+        }
+        let names: Vec<&str> = names.iter().map(|n| n.as_str()).collect();
+        let data: Vec<RawVal> = vec![
+            exceeded.limit.try_into_val(self)?,
+            exceeded.total_count.try_into_val(self)?,
+            self.map_new_from_slices(&names, &counts)?.into(),
+        ];
+        self.record_system_debug_contract_event(
+            ContractEventType::Diagnostic,
+            contract_id,
+            self.add_host_object(HostVec::from_vec(topics)?)?
+                .try_into()?,
+            self.vec_new_from_slice(&data)?.into(),
+        )
+    }
 }
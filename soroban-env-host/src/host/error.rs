@@ -1,5 +1,6 @@
 use crate::{
-    events::{Event, Events},
+    budget::{BudgetExceeded, LedgerIoType},
+    events::{Events, EventsTrace},
     xdr::{self, ScStatus},
     Status,
 };
@@ -11,6 +12,10 @@ pub struct HostError {
     pub status: Status,
     pub(crate) events: Option<Events>,
     pub(crate) backtrace: backtrace::Backtrace,
+    /// Set if the error has been caused by exceeding the budget.
+    pub(crate) budget_exceeded: Option<Box<BudgetExceeded>>,
+    /// Set if the error has been caused by exceeding a ledger IO limit.
+    pub(crate) ledger_io_exceeded: Option<LedgerIoType>,
 }
 
 impl std::error::Error for HostError {}
@@ -56,34 +61,19 @@ impl Debug for HostError {
         let bt: Backtrace = frames.into();
         writeln!(f, "HostError")?;
         writeln!(f, "Value: {:?}", self.status)?;
-        // TODO: maybe make this something users can adjust?
-        const MAX_DEBUG_EVENTS: usize = 10;
-        match &self.events {
-            None => (),
-            Some(ev) => {
-                let mut wrote_heading = false;
-                for (i, e) in
-                    ev.0.iter()
-                        .rev()
-                        .filter_map(|ev| match ev.event.clone() {
-                            Event::Debug(e) => Some(format!("Debug {:}", e)),
-                            Event::StructuredDebug(e) => Some(format!("StructuredDebug {:?}", e)),
-                            _ => None,
-                        })
-                        .take(MAX_DEBUG_EVENTS)
-                        .enumerate()
-                {
-                    if !wrote_heading {
-                        writeln!(f, "")?;
-                        writeln!(f, "Debug events (newest first):")?;
-                        wrote_heading = true;
-                    }
-                    writeln!(f, "   {:?}: {:?}", i, e)?;
-                }
-                if ev.0.len() > MAX_DEBUG_EVENTS {
-                    writeln!(f, "   {:?}: ... elided ...", MAX_DEBUG_EVENTS)?;
-                }
-            }
+        if let Some(trace) = self.trace() {
+            writeln!(f, "")?;
+            writeln!(f, "Events trace:")?;
+            write!(f, "{}", trace)?;
+        }
+        if let Some(exceeded) = &self.budget_exceeded {
+            writeln!(f, "")?;
+            writeln!(f, "Budget exceeded:")?;
+            write!(f, "{}", exceeded)?;
+        }
+        if let Some(ty) = self.ledger_io_exceeded {
+            writeln!(f, "")?;
+            writeln!(f, "Ledger IO limit exceeded: {:?}", ty)?;
         }
         writeln!(f, "")?;
         writeln!(f, "Backtrace (newest first):")?;
@@ -92,6 +82,25 @@ impl Debug for HostError {
 }
 
 impl HostError {
+    /// Returns the diagnostics of the budget exhaustion that caused this
+    /// error, if any.
+    pub fn budget_exceeded(&self) -> Option<&BudgetExceeded> {
+        self.budget_exceeded.as_deref()
+    }
+
+    /// Returns the ledger IO resource whose limit has been exceeded, if that
+    /// is what caused this error.
+    pub fn ledger_io_exceeded(&self) -> Option<LedgerIoType> {
+        self.ledger_io_exceeded
+    }
+
+    /// Returns the trace of the events recorded up to this error, if they
+    /// have been captured along with it. The calls unwound by the error are
+    /// the ones that haven't returned by the end of the trace.
+    pub fn trace(&self) -> Option<EventsTrace<'_>> {
+        self.events.as_ref().map(|events| events.trace())
+    }
+
     #[cfg(test)]
     pub fn result_matches_err_status<T, C>(res: Result<T, HostError>, code: C) -> bool
     where
@@ -119,6 +128,8 @@ where
             status,
             events,
             backtrace,
+            budget_exceeded: None,
+            ledger_io_exceeded: None,
         }
     }
 }
@@ -1,6 +1,7 @@
 use crate::{
+    budget::Budget,
     xdr::ContractCostType,
-    xdr::{ReadXdr, ScBytes, WriteXdr},
+    xdr::{ReadXdr, ScBytes, ScUnknownErrorCode, WriteXdr},
     BytesObject, Host, HostError,
 };
 use std::{error::Error, io::Write};
@@ -8,7 +9,7 @@ use std::{error::Error, io::Write};
 use sha2::{Digest, Sha256};
 
 struct MeteredWrite<'a, W: Write> {
-    host: &'a Host,
+    budget: &'a Budget,
     w: &'a mut W,
 }
 
@@ -17,8 +18,8 @@ where
     W: Write,
 {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        self.host
-            .charge_budget(ContractCostType::ValSer, Some(buf.len() as u64))
+        self.budget
+            .charge(ContractCostType::ValSer, Some(buf.len() as u64))
             .map_err(|e| Into::<std::io::Error>::into(e))?;
         self.w.write(buf)
     }
@@ -28,32 +29,42 @@ where
     }
 }
 
+// Writes `obj` into `w`, charging `ValSer` for every chunk as it gets written.
+// If the write fails because of the budget, returns the budget's error.
+fn write_xdr_charging(
+    budget: &Budget,
+    obj: &impl WriteXdr,
+    w: &mut Vec<u8>,
+) -> Result<(), Option<HostError>> {
+    let mut w = MeteredWrite { budget, w };
+    obj.write_xdr(&mut w).map_err(|e| {
+        e.source()
+            .and_then(|e2| e2.downcast_ref::<std::io::Error>())
+            .and_then(|e3| e3.get_ref())
+            .and_then(|e4| e4.downcast_ref::<HostError>())
+            .cloned()
+    })
+}
+
+/// Same as [Host::metered_write_xdr], for the code that only has access to
+/// the [Budget].
+pub(crate) fn metered_write_xdr(
+    budget: &Budget,
+    obj: &impl WriteXdr,
+    w: &mut Vec<u8>,
+) -> Result<(), HostError> {
+    write_xdr_charging(budget, obj, w)
+        .map_err(|e| e.unwrap_or_else(|| ScUnknownErrorCode::Xdr.into()))
+}
+
 impl Host {
     pub(crate) fn metered_write_xdr(
         &self,
         obj: &impl WriteXdr,
         w: &mut Vec<u8>,
     ) -> Result<(), HostError> {
-        let mut w = MeteredWrite { host: &self, w };
-        obj.write_xdr(&mut w).map_err(|e| {
-            if let Some(e2) = e.source() {
-                if let Some(e3) = (*e2).downcast_ref::<std::io::Error>() {
-                    if let Some(e4) = e3.get_ref() {
-                        if let Some(e5) = e4.downcast_ref::<HostError>() {
-                            e5.clone()
-                        } else {
-                            self.err_general("failed to write xdr")
-                        }
-                    } else {
-                        self.err_general("failed to write xdr")
-                    }
-                } else {
-                    self.err_general("failed to write xdr")
-                }
-            } else {
-                self.err_general("failed to write xdr")
-            }
-        })
+        write_xdr_charging(self.budget_ref(), obj, w)
+            .map_err(|e| e.unwrap_or_else(|| self.err_general("failed to write xdr")))
     }
 
     pub(crate) fn metered_hash_xdr(&self, obj: &impl WriteXdr) -> Result<[u8; 32], HostError> {
@@ -0,0 +1,202 @@
+use crate::xdr::{ScHostFnErrorCode, ScVmErrorCode};
+use soroban_env_common::num::RoundingMode;
+
+use crate::{
+    budget::{AsBudget, HostCostType},
+    Host, HostError, I128Object, I128Small, I128Val, I256Object, I256Small, I256Val, U128Object,
+    U128Small, U128Val, U256Object, U256Small, U256Val, U32Val, I256, U256,
+};
+
+// The integer types the arithmetic host functions operate on, along with the
+// value types they're passed as: the small values are packed into the
+// `RawVal`s and the other ones are passed as objects.
+pub(crate) trait HostInt: Copy + Default + PartialEq {
+    type Val: Copy;
+
+    fn from_val(host: &Host, val: Self::Val) -> Result<Self, HostError>;
+
+    fn into_val(self, host: &Host) -> Result<Self::Val, HostError>;
+}
+
+macro_rules! impl_host_int {
+    ($T:ty, $VAL:ty, $SMALL:ty, $OBJ:ty) => {
+        impl HostInt for $T {
+            type Val = $VAL;
+
+            // Notes on metering: objects covered by `visit_obj`, small values
+            // free.
+            fn from_val(host: &Host, val: $VAL) -> Result<Self, HostError> {
+                if let Ok(small) = <$SMALL>::try_from(val) {
+                    return Ok(small.into());
+                }
+                let obj = <$OBJ>::try_from(val).map_err(|_| {
+                    host.err_conversion_general(concat!("expected ", stringify!($T), " object"))
+                })?;
+                host.visit_obj(obj, |i: &$T| Ok(*i))
+            }
+
+            // Notes on metering: objects covered by `add_host_object`, small
+            // values free.
+            fn into_val(self, host: &Host) -> Result<$VAL, HostError> {
+                match <$SMALL>::try_from(self) {
+                    Ok(small) => Ok(small.into()),
+                    Err(_) => Ok(host.add_host_object(self)?.into()),
+                }
+            }
+        }
+    };
+}
+
+impl_host_int!(u128, U128Val, U128Small, U128Object);
+impl_host_int!(i128, I128Val, I128Small, I128Object);
+impl_host_int!(U256, U256Val, U256Small, U256Object);
+impl_host_int!(I256, I256Val, I256Small, I256Object);
+
+impl Host {
+    fn err_int_overflow(&self) -> HostError {
+        self.err_status_msg(ScVmErrorCode::TrapIntegerOverflow, "integer overflow")
+    }
+
+    // Applies a checked binary operation, `None` standing for an overflow.
+    // Notes on metering: `ty` is charged for the operation, the operands and
+    // the result are covered by the conversions. The 128-bit operations are
+    // charged as 256-bit ones, which bound their costs.
+    pub(crate) fn int_binop<T: HostInt>(
+        &self,
+        ty: HostCostType,
+        lhs: T::Val,
+        rhs: T::Val,
+        op: impl FnOnce(T, T) -> Option<T>,
+    ) -> Result<T::Val, HostError> {
+        self.charge_budget(ty, None)?;
+        let lhs = T::from_val(self, lhs)?;
+        let rhs = T::from_val(self, rhs)?;
+        match op(lhs, rhs) {
+            Some(res) => res.into_val(self),
+            None => Err(self.err_int_overflow()),
+        }
+    }
+
+    // Same as `int_binop`, for the division and the remainder, which fail
+    // on a zero `rhs` as well as on overflow.
+    // Notes on metering: charged as `Int256Div`, see `int_binop`.
+    pub(crate) fn int_div_op<T: HostInt>(
+        &self,
+        lhs: T::Val,
+        rhs: T::Val,
+        op: impl FnOnce(T, T) -> Option<T>,
+    ) -> Result<T::Val, HostError> {
+        self.charge_budget(HostCostType::Int256Div, None)?;
+        let lhs = T::from_val(self, lhs)?;
+        let rhs = T::from_val(self, rhs)?;
+        if rhs == T::default() {
+            return Err(self.err_status_msg(ScVmErrorCode::TrapDivisionByZero, "division by zero"));
+        }
+        match op(lhs, rhs) {
+            Some(res) => res.into_val(self),
+            None => Err(self.err_int_overflow()),
+        }
+    }
+
+    // Notes on metering: exponentiation by squaring does up to two
+    // multiplications per bit of the exponent, each charged as `Int256Mul`.
+    pub(crate) fn int_pow<T: HostInt>(
+        &self,
+        lhs: T::Val,
+        rhs: U32Val,
+        op: impl FnOnce(T, u32) -> Option<T>,
+    ) -> Result<T::Val, HostError> {
+        let exp = u32::from(rhs);
+        let muls = 2 * u64::from(u32::BITS - exp.leading_zeros());
+        self.as_budget()
+            .batched_charge(HostCostType::Int256Mul, muls, None)?;
+        let lhs = T::from_val(self, lhs)?;
+        match op(lhs, exp) {
+            Some(res) => res.into_val(self),
+            None => Err(self.err_int_overflow()),
+        }
+    }
+
+    // Applies a checked shift, `None` standing for a shift by at least the
+    // number of bits in `T`.
+    // Notes on metering: charged as `Int256Shift`, see `int_binop`.
+    pub(crate) fn int_shift<T: HostInt>(
+        &self,
+        lhs: T::Val,
+        rhs: U32Val,
+        op: impl FnOnce(T, u32) -> Option<T>,
+    ) -> Result<T::Val, HostError> {
+        self.charge_budget(HostCostType::Int256Shift, None)?;
+        let lhs = T::from_val(self, lhs)?;
+        match op(lhs, rhs.into()) {
+            Some(res) => res.into_val(self),
+            None => Err(self.err_status_msg(
+                ScHostFnErrorCode::InputArgsInvalid,
+                "shift amount out of range",
+            )),
+        }
+    }
+
+    // Notes on metering: `costs` gives the number of each operation `op`
+    // does on the operands `a` and `b`, which are charged once the operands
+    // are converted.
+    pub(crate) fn int_mul_div<T: HostInt, const N: usize>(
+        &self,
+        a: T::Val,
+        b: T::Val,
+        c: T::Val,
+        rounding: U32Val,
+        costs: impl FnOnce(&T, &T) -> [(HostCostType, u64); N],
+        op: impl FnOnce(T, T, T, RoundingMode) -> Option<T>,
+    ) -> Result<T::Val, HostError> {
+        let rounding = RoundingMode::try_from(u32::from(rounding)).map_err(|_| {
+            self.err_status_msg_with_args(
+                ScHostFnErrorCode::InputArgsInvalid,
+                "unknown rounding mode",
+                &[rounding.into()],
+            )
+        })?;
+        let a = T::from_val(self, a)?;
+        let b = T::from_val(self, b)?;
+        let c = T::from_val(self, c)?;
+        for (ty, iterations) in costs(&a, &b) {
+            self.as_budget().batched_charge(ty, iterations, None)?;
+        }
+        if c == T::default() {
+            return Err(self.err_status_msg(ScVmErrorCode::TrapDivisionByZero, "division by zero"));
+        }
+        match op(a, b, c, rounding) {
+            Some(res) => res.into_val(self),
+            None => Err(self.err_int_overflow()),
+        }
+    }
+}
+
+// The operations done by `i128_mul_div`: the 256-bit product, the division
+// and the remainder, and the comparisons and additions of the rounding and
+// of the range check.
+pub(crate) fn i128_mul_div_costs(_a: &i128, _b: &i128) -> [(HostCostType, u64); 3] {
+    [
+        (HostCostType::Int256Mul, 2),
+        (HostCostType::Int256Div, 2),
+        (HostCostType::Int256AddSub, 8),
+    ]
+}
+
+// The operations done by `u256_mul_div`: the 512-bit product of four 128-bit
+// ones, then either a 256-bit division and remainder if the product fits in
+// 256 bits, or else the long division of `u512_div_rem`, which takes 256
+// steps of 4 shifts and 5 additions, comparisons or bitwise operations. The
+// product fitting in 256 bits is checked with one more multiplication.
+pub(crate) fn u256_mul_div_costs(a: &U256, b: &U256) -> [(HostCostType, u64); 4] {
+    let (div, steps) = match a.checked_mul(*b) {
+        Some(_) => (2, 0),
+        None => (0, 256),
+    };
+    [
+        (HostCostType::Int256Mul, 4 + 1),
+        (HostCostType::Int256Div, div),
+        (HostCostType::Int256Shift, 4 + 4 * steps),
+        (HostCostType::Int256AddSub, 13 + 5 * steps),
+    ]
+}
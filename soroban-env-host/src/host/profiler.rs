@@ -0,0 +1,143 @@
+use std::fmt::Display;
+
+use soroban_env_common::xdr::Hash;
+
+use crate::{Host, HostError};
+
+use super::Frame;
+
+/// A single call in the tree built by the cost profiler: the CPU and memory
+/// charged while a [Frame] was on the stack, and the calls made from it.
+///
+/// The "self" costs only cover the charges made while the frame was the
+/// innermost one, while the "inclusive" costs also cover the nested calls.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProfileNode {
+    /// Id of the called contract, if the frame belongs to one.
+    pub contract_id: Option<Hash>,
+    /// Name of the called function (or host function type).
+    pub function: String,
+    pub self_cpu_insns: u64,
+    pub self_mem_bytes: u64,
+    pub inclusive_cpu_insns: u64,
+    pub inclusive_mem_bytes: u64,
+    pub children: Vec<ProfileNode>,
+}
+
+impl ProfileNode {
+    fn fmt_indented(&self, f: &mut std::fmt::Formatter<'_>, depth: usize) -> std::fmt::Result {
+        let name = match &self.contract_id {
+            Some(id) => format!("{}:{}", hex::encode(id.0), self.function),
+            None => self.function.clone(),
+        };
+        writeln!(
+            f,
+            "{:indent$}{} cpu: {} ({} self), mem: {} ({} self)",
+            "",
+            name,
+            self.inclusive_cpu_insns,
+            self.self_cpu_insns,
+            self.inclusive_mem_bytes,
+            self.self_mem_bytes,
+            indent = depth * 2
+        )?;
+        for child in self.children.iter() {
+            child.fmt_indented(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for ProfileNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+/// Builds the [ProfileNode] tree from the budget counts observed as frames
+/// are pushed and popped.
+#[derive(Clone, Default)]
+pub(crate) struct CostProfiler {
+    /// Nodes of the frames currently on the stack, along with the CPU and
+    /// memory counts at the time the frame was pushed.
+    stack: Vec<(ProfileNode, u64, u64)>,
+    /// Calls made with no frame on the stack.
+    roots: Vec<ProfileNode>,
+}
+
+impl CostProfiler {
+    fn push(&mut self, contract_id: Option<Hash>, function: String, cpu: u64, mem: u64) {
+        let node = ProfileNode {
+            contract_id,
+            function,
+            ..Default::default()
+        };
+        self.stack.push((node, cpu, mem));
+    }
+
+    fn pop(&mut self, cpu: u64, mem: u64) {
+        let (mut node, start_cpu, start_mem) = match self.stack.pop() {
+            Some(top) => top,
+            // Profiling has been enabled with frames already on the stack.
+            None => return,
+        };
+        node.inclusive_cpu_insns = cpu.saturating_sub(start_cpu);
+        node.inclusive_mem_bytes = mem.saturating_sub(start_mem);
+        let (children_cpu, children_mem) =
+            node.children.iter().fold((0_u64, 0_u64), |(cpu, mem), c| {
+                (
+                    cpu.saturating_add(c.inclusive_cpu_insns),
+                    mem.saturating_add(c.inclusive_mem_bytes),
+                )
+            });
+        node.self_cpu_insns = node.inclusive_cpu_insns.saturating_sub(children_cpu);
+        node.self_mem_bytes = node.inclusive_mem_bytes.saturating_sub(children_mem);
+        match self.stack.last_mut() {
+            Some((parent, _, _)) => parent.children.push(node),
+            None => self.roots.push(node),
+        }
+    }
+}
+
+impl Host {
+    /// Starts attributing the CPU and memory charges to the frames pushed
+    /// from now on. Any previously collected profile is discarded.
+    pub fn enable_cost_profiling(&self) {
+        *self.0.profiler.borrow_mut() = Some(CostProfiler::default());
+    }
+
+    /// Returns the calls profiled since [Host::enable_cost_profiling] that
+    /// have finished, in the order they have been made.
+    pub fn cost_profile(&self) -> Vec<ProfileNode> {
+        match &*self.0.profiler.borrow() {
+            Some(profiler) => profiler.roots.clone(),
+            None => vec![],
+        }
+    }
+
+    // Profiling doesn't charge the budget, so that the profile describes the
+    // same costs as an unprofiled run.
+    pub(crate) fn profile_push_frame(&self, frame: &Frame) -> Result<(), HostError> {
+        if self.0.profiler.borrow().is_none() {
+            return Ok(());
+        }
+        let budget = self.budget_ref();
+        let (contract_id, function) = budget.with_free_budget(|| self.describe_frame(frame))?;
+        if let Some(profiler) = self.0.profiler.borrow_mut().as_mut() {
+            profiler.push(
+                contract_id,
+                function,
+                budget.get_cpu_insns_count(),
+                budget.get_mem_bytes_count(),
+            );
+        }
+        Ok(())
+    }
+
+    pub(crate) fn profile_pop_frame(&self) {
+        if let Some(profiler) = self.0.profiler.borrow_mut().as_mut() {
+            let budget = self.budget_ref();
+            profiler.pop(budget.get_cpu_insns_count(), budget.get_mem_bytes_count());
+        }
+    }
+}
@@ -39,13 +39,15 @@ pub mod auth;
 #[cfg(feature = "vm")]
 pub mod vm;
 #[cfg(feature = "vm")]
-pub use vm::Vm;
+pub use vm::{ContractSpec, FunctionSpec, ModuleCache, Vm};
 #[cfg(any(test, feature = "testutils"))]
 pub mod cost_runner;
 pub mod storage;
 #[cfg(test)]
 mod test;
 
+#[cfg(any(test, feature = "testutils"))]
+pub use host::profiler::ProfileNode;
 #[cfg(any(test, feature = "testutils"))]
 #[doc(hidden)]
 pub use host::testutils::call_with_suppressed_panic_hook;
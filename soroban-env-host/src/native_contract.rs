@@ -12,6 +12,7 @@ pub trait NativeContract {
 pub use token::Token;
 
 pub(crate) mod account_contract;
+pub(crate) mod invoker_contract_auth;
 
 #[cfg(test)]
 pub(crate) mod testutils;
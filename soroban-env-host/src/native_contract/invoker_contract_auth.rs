@@ -0,0 +1,79 @@
+use crate::auth::AuthorizedInvocation;
+use crate::host::Host;
+use crate::native_contract::base_types::{BytesN, Vec as HostVec};
+use crate::HostError;
+use soroban_env_common::xdr::{ContractCostType, Hash, ScHostFnErrorCode, ScVal};
+use soroban_env_common::{RawVal, Symbol, TryFromVal, TryIntoVal, VecObject};
+use soroban_native_sdk_macros::contracttype;
+
+// Maximum depth of an invocation tree a contract can authorize on its own
+// behalf, counting the root invocation.
+pub(crate) const MAX_SUB_CONTRACT_INVOCATION_DEPTH: u32 = 16;
+
+// An invocation that a contract authorizes on its own behalf, together with
+// the sub-invocations of it that are authorized as well. `sub_invocations`
+// contains values of this same type.
+#[derive(Clone)]
+#[contracttype]
+pub struct SubContractInvocation {
+    pub contract: BytesN<32>,
+    pub fn_name: Symbol,
+    pub args: HostVec,
+    pub sub_invocations: HostVec,
+}
+
+impl SubContractInvocation {
+    // Every level of the tree is charged as an object visit, and the trees
+    // deeper than `MAX_SUB_CONTRACT_INVOCATION_DEPTH` are rejected.
+    fn to_authorized_invocation(
+        &self,
+        host: &Host,
+        depth: u32,
+    ) -> Result<AuthorizedInvocation, HostError> {
+        if depth >= MAX_SUB_CONTRACT_INVOCATION_DEPTH {
+            return Err(host.err_status_msg(
+                ScHostFnErrorCode::InputArgsInvalid,
+                "invoker contract auth entry is nested too deeply",
+            ));
+        }
+        host.charge_budget(ContractCostType::VisitObject, None)?;
+        let ScVal::Symbol(function_name) = host.from_host_val(self.fn_name.to_raw())? else {
+            return Err(host.err_status_msg(
+                ScHostFnErrorCode::InputArgsInvalid,
+                "invalid function name in invoker contract auth entry",
+            ));
+        };
+        let mut sub_invocations = vec![];
+        for i in 0..self.sub_invocations.len()? {
+            let sub_invocation: SubContractInvocation = self.sub_invocations.get(i)?;
+            sub_invocations.push(sub_invocation.to_authorized_invocation(host, depth + 1)?);
+        }
+        Ok(AuthorizedInvocation::new(
+            Hash(self.contract.to_array()?),
+            function_name,
+            host.call_args_to_scvec(self.args.clone().into())?,
+            sub_invocations,
+        ))
+    }
+}
+
+// Converts the auth entries passed to `authorize_as_curr_contract` into the
+// invocation trees to track on behalf of the current contract.
+pub(crate) fn invoker_contract_auth_to_authorized_invocations(
+    host: &Host,
+    auth_entries: VecObject,
+) -> Result<Vec<AuthorizedInvocation>, HostError> {
+    let entries = HostVec::try_from_val(host, &auth_entries)?;
+    let mut invocations = vec![];
+    for i in 0..entries.len()? {
+        let entry: RawVal = entries.get(i)?;
+        let entry = SubContractInvocation::try_from_val(host, &entry).map_err(|_| {
+            host.err_status_msg(
+                ScHostFnErrorCode::InputArgsInvalid,
+                "invalid invoker contract auth entry",
+            )
+        })?;
+        invocations.push(entry.to_authorized_invocation(host, 0)?);
+    }
+    Ok(invocations)
+}
@@ -6,19 +6,82 @@
 //!   - [Env::get_contract_data](crate::Env::get_contract_data)
 //!   - [Env::put_contract_data](crate::Env::put_contract_data)
 //!   - [Env::del_contract_data](crate::Env::del_contract_data)
+//!   - [Env::bump_contract_data](crate::Env::bump_contract_data)
+//!   - [Env::has_expiring_contract_data](crate::Env::has_expiring_contract_data)
+//!   - [Env::get_expiring_contract_data](crate::Env::get_expiring_contract_data)
+//!   - [Env::put_expiring_contract_data](crate::Env::put_expiring_contract_data)
+//!   - [Env::del_expiring_contract_data](crate::Env::del_expiring_contract_data)
 
 use std::rc::Rc;
 
 use soroban_env_common::{Compare, RawVal};
 
-use crate::budget::Budget;
-use crate::xdr::{Hash, LedgerEntry, LedgerKey, ScHostStorageErrorCode};
+use crate::budget::{Budget, LedgerIoType};
+use crate::host::metered_xdr::metered_write_xdr;
+use crate::xdr::{
+    Hash, LedgerEntry, LedgerKey, ScAddress, ScHostStorageErrorCode, ScMap, ScMapEntry, ScNonceKey,
+    ScUnknownErrorCode, ScVal,
+};
 use crate::Host;
 use crate::{host::metered_map::MeteredOrdMap, HostError};
 
+mod changes;
+mod snapshot;
+pub use changes::{LedgerChanges, LedgerEntryChange};
+pub use snapshot::{ledger_key_from_entry, LayeredSnapshot, MemorySnapshot, SnapshotFileFormat};
+
 pub type FootprintMap = MeteredOrdMap<Rc<LedgerKey>, AccessType, Budget>;
 pub type StorageMap = MeteredOrdMap<Rc<LedgerKey>, Option<Rc<LedgerEntry>>, Budget>;
 pub type TempStorageMap = MeteredOrdMap<Rc<(Hash, RawVal)>, RawVal, Host>;
+pub type ExpirationMap = MeteredOrdMap<Rc<LedgerKey>, u32, Budget>;
+pub type ReadCountMap = MeteredOrdMap<Rc<LedgerKey>, u32, Budget>;
+pub type EntrySizeMap = MeteredOrdMap<Rc<LedgerKey>, u32, Budget>;
+
+/// Returns the ledger-level key of the expiring contract data entry that
+/// `contract_id` stores under `key`.
+///
+/// Expiring entries live in the ledger alongside the persistent contract
+/// data, but always expire and can't be bumped. [LedgerKey] has no notion of
+/// the entry class, so the key provided by the contract is stored as the value
+/// of a single-entry `ScVal::Map` keyed by the `ScVal::LedgerKeyNonce` of the
+/// contract's own address. Contracts can't build `LedgerKeyNonce` values, so
+/// none of the persistent keys can take this shape.
+pub fn expiring_key_scval(contract_id: Hash, key: ScVal) -> Result<ScVal, HostError> {
+    let discriminator = ScVal::LedgerKeyNonce(ScNonceKey {
+        nonce_address: ScAddress::Contract(contract_id),
+    });
+    Ok(ScVal::Map(Some(ScMap(
+        vec![ScMapEntry {
+            key: discriminator,
+            val: key,
+        }]
+        .try_into()?,
+    ))))
+}
+
+/// Returns `true` if the given contract data key has the shape of the
+/// expiring entries (see [expiring_key_scval]).
+pub fn is_expiring_key_scval(key: &ScVal) -> bool {
+    match key {
+        ScVal::Map(Some(m)) => matches!(
+            m.as_slice(),
+            [ScMapEntry {
+                key: ScVal::LedgerKeyNonce(_),
+                ..
+            }]
+        ),
+        _ => false,
+    }
+}
+
+/// Returns `true` if the given [LedgerKey] points at an expiring contract
+/// data entry.
+pub fn is_expiring_key(key: &LedgerKey) -> bool {
+    match key {
+        LedgerKey::ContractData(k) => is_expiring_key_scval(&k.key),
+        _ => false,
+    }
+}
 
 /// A helper type used by [Footprint] to designate which ways
 /// a given [LedgerKey] is accessed, or is allowed to be accessed,
@@ -54,6 +117,12 @@ impl Compare<AccessType> for Budget {
 pub trait SnapshotSource {
     fn get(&self, key: &Rc<LedgerKey>) -> Result<Rc<LedgerEntry>, HostError>;
     fn has(&self, key: &Rc<LedgerKey>) -> Result<bool, HostError>;
+    /// Returns the last ledger sequence number at which the entry under the
+    /// given [LedgerKey] is still live, or `None` if the entry never expires
+    /// (or the source doesn't track expiration).
+    fn get_expiration(&self, _key: &Rc<LedgerKey>) -> Result<Option<u32>, HostError> {
+        Ok(None)
+    }
 }
 
 /// Describes the total set of [LedgerKey]s that a given transaction
@@ -139,11 +208,44 @@ impl Default for FootprintMode {
 /// "preflight" execution of a contract. Once calculated, a recorded [Footprint]
 /// can be provided to "real" execution, which always runs in
 /// [FootprintMode::Enforcing] mode and enforces partitioned access.
+///
+/// Alongside the entries themselves, [Storage] tracks the expiration ledger
+/// of each entry that has one (entries without an expiration never expire).
+/// In [FootprintMode::Enforcing] mode the `expiration_map` has to be
+/// populated by the embedder together with the `map`; in
+/// [FootprintMode::Recording] mode it is read through from the
+/// [SnapshotSource]. Every expiration extension performed during the run is
+/// also recorded in `bumps`, so that the cost of the extensions can be
+/// accounted for in preflight.
+///
+/// Every access is also charged to the [Budget]'s ledger IO
+/// ([LedgerIoType]): the first access to a key charges one read entry and
+/// the XDR size of the entry as it was before the run, the first write to a
+/// key charges one write entry, and every write that grows the entry beyond
+/// the largest size written so far charges the difference.
 #[derive(Clone, Default)]
 pub struct Storage {
     pub footprint: Footprint,
     pub mode: FootprintMode,
     pub map: StorageMap,
+    pub expiration_map: ExpirationMap,
+    pub bumps: ExpirationMap,
+    /// Number of reads of each [LedgerKey], only tracked in
+    /// [FootprintMode::Recording] mode. Used for the [FootprintReport].
+    pub read_counts: ReadCountMap,
+    /// Number of reads of each written [LedgerKey] that happened before its
+    /// first write, only tracked in [FootprintMode::Recording] mode. Used for
+    /// the [FootprintReport].
+    pub reads_before_write: ReadCountMap,
+    /// Number of writes of each [LedgerKey] that changed either the entry or
+    /// its expiration, only tracked in [FootprintMode::Recording] mode. Used
+    /// for the [FootprintReport].
+    pub changing_writes: ReadCountMap,
+    /// XDR size of each accessed entry before its first access (0 for the
+    /// missing entries).
+    pub read_sizes: EntrySizeMap,
+    /// Largest XDR size written for each written or deleted entry.
+    pub write_sizes: EntrySizeMap,
 }
 
 // Notes on metering: all storage operations: `put`, `get`, `del`, `has` are
@@ -157,6 +259,36 @@ impl Storage {
             mode: FootprintMode::Enforcing,
             footprint,
             map,
+            expiration_map: Default::default(),
+            bumps: Default::default(),
+            read_counts: Default::default(),
+            reads_before_write: Default::default(),
+            changing_writes: Default::default(),
+            read_sizes: Default::default(),
+            write_sizes: Default::default(),
+        }
+    }
+
+    /// Constructs a new [Storage] in [FootprintMode::Enforcing] using a
+    /// given [Footprint], a storage map populated with all the keys listed
+    /// in the [Footprint] and the expiration ledgers of those entries that
+    /// have one.
+    pub fn with_enforcing_footprint_map_and_expirations(
+        footprint: Footprint,
+        map: StorageMap,
+        expiration_map: ExpirationMap,
+    ) -> Self {
+        Self {
+            mode: FootprintMode::Enforcing,
+            footprint,
+            map,
+            expiration_map,
+            bumps: Default::default(),
+            read_counts: Default::default(),
+            reads_before_write: Default::default(),
+            changing_writes: Default::default(),
+            read_sizes: Default::default(),
+            write_sizes: Default::default(),
         }
     }
 
@@ -167,6 +299,13 @@ impl Storage {
             mode: FootprintMode::Recording(src),
             footprint: Footprint::default(),
             map: Default::default(),
+            expiration_map: Default::default(),
+            bumps: Default::default(),
+            read_counts: Default::default(),
+            reads_before_write: Default::default(),
+            changing_writes: Default::default(),
+            read_sizes: Default::default(),
+            write_sizes: Default::default(),
         }
     }
 
@@ -190,6 +329,8 @@ impl Storage {
         match self.mode {
             FootprintMode::Recording(ref src) => {
                 self.footprint.record_access(key, ty, budget)?;
+                self.read_counts = count_access(&self.read_counts, key, budget)?;
+                self.meter_read(key, budget)?;
                 // In recording mode we treat the map as a cache
                 // that misses read-through to the underlying src.
                 if !self.map.contains_key::<Rc<LedgerKey>>(key, budget)? {
@@ -200,6 +341,7 @@ impl Storage {
             }
             FootprintMode::Enforcing => {
                 self.footprint.enforce_access(key, ty, budget)?;
+                self.meter_read(key, budget)?;
             }
         };
         match self.map.get::<Rc<LedgerKey>>(key, budget)? {
@@ -216,20 +358,87 @@ impl Storage {
         budget: &Budget,
     ) -> Result<(), HostError> {
         let ty = AccessType::ReadWrite;
+        let unchanged = self.is_unchanged_by(key, val, budget)?;
         match self.mode {
             FootprintMode::Recording(_) => {
-                self.footprint.record_access(key, ty, budget)?;
+                self.record_write(key, !unchanged, budget)?;
             }
             FootprintMode::Enforcing => {
+                // A write that leaves the entry as it is doesn't modify the
+                // ledger, so it is allowed for the read-only keys too.
+                if unchanged
+                    && self.footprint.0.get::<Rc<LedgerKey>>(key, budget)?
+                        == Some(&AccessType::ReadOnly)
+                {
+                    return self.meter_read(key, budget);
+                }
                 self.footprint.enforce_access(key, ty, budget)?;
             }
         };
+        // A written entry is also read: its previous version is part of the
+        // metadata, and the read entries declared by a transaction include
+        // the written ones.
+        self.meter_read(key, budget)?;
+        self.meter_write(key, val, budget)?;
         self.map = self
             .map
             .insert(Rc::clone(key), val.map(|v| Rc::clone(v)), budget)?;
         Ok(())
     }
 
+    /// Records a write of `key` in [FootprintMode::Recording] mode:
+    /// upgrades its access to [AccessType::ReadWrite], noting the reads that
+    /// preceded the first write, and counts the write if it is a `changing`
+    /// one.
+    fn record_write(
+        &mut self,
+        key: &Rc<LedgerKey>,
+        changing: bool,
+        budget: &Budget,
+    ) -> Result<(), HostError> {
+        if self.footprint.0.get::<Rc<LedgerKey>>(key, budget)? != Some(&AccessType::ReadWrite) {
+            let reads = self
+                .read_counts
+                .get::<Rc<LedgerKey>>(key, budget)?
+                .copied()
+                .unwrap_or(0);
+            self.reads_before_write =
+                self.reads_before_write
+                    .insert(Rc::clone(key), reads, budget)?;
+        }
+        self.footprint
+            .record_access(key, AccessType::ReadWrite, budget)?;
+        if changing {
+            self.changing_writes = count_access(&self.changing_writes, key, budget)?;
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if writing `val` under `key` would leave the entry as
+    /// it currently is. Only the entry data is compared, as the entries
+    /// loaded from the ledger have their `last_modified_ledger_seq` set.
+    fn is_unchanged_by(
+        &self,
+        key: &Rc<LedgerKey>,
+        val: Option<&Rc<LedgerEntry>>,
+        budget: &Budget,
+    ) -> Result<bool, HostError> {
+        let current = match self.map.get::<Rc<LedgerKey>>(key, budget)? {
+            Some(entry) => entry.clone(),
+            None => match self.mode {
+                FootprintMode::Recording(ref src) if src.has(key)? => Some(src.get(key)?),
+                _ => None,
+            },
+        };
+        match (current, val) {
+            (Some(c), Some(v)) => {
+                Ok(budget.compare(&c.data, &v.data)? == core::cmp::Ordering::Equal)
+            }
+            (None, None) => Ok(true),
+            _ => Ok(false),
+        }
+    }
+
     /// Attempts to write to the [LedgerEntry] associated with a given
     /// [LedgerKey] in the [Storage].
     ///
@@ -238,7 +447,8 @@ impl Storage {
     ///
     /// In [FootprintMode::Enforcing] mode, succeeds only if the written
     /// [LedgerKey] has been declared in the [Footprint] as
-    /// [AccessType::ReadWrite].
+    /// [AccessType::ReadWrite], or if it has been declared as
+    /// [AccessType::ReadOnly] and the write leaves the entry unchanged.
     pub fn put(
         &mut self,
         key: &Rc<LedgerKey>,
@@ -256,7 +466,8 @@ impl Storage {
     ///
     /// In [FootprintMode::Enforcing] mode, succeeds only if the deleted
     /// [LedgerKey] has been declared in the [Footprint] as
-    /// [AccessType::ReadWrite].
+    /// [AccessType::ReadWrite], or if it has been declared as
+    /// [AccessType::ReadOnly] and there is no entry to delete.
     pub fn del(&mut self, key: &Rc<LedgerKey>, budget: &Budget) -> Result<(), HostError> {
         self.put_opt(key, None, budget)
     }
@@ -275,6 +486,8 @@ impl Storage {
         match self.mode {
             FootprintMode::Recording(ref src) => {
                 self.footprint.record_access(key, ty, budget)?;
+                self.read_counts = count_access(&self.read_counts, key, budget)?;
+                self.meter_read(key, budget)?;
                 // We don't cache has() calls but we do
                 // consult the cache before answering them.
                 match self.map.get::<Rc<LedgerKey>>(key, budget)? {
@@ -285,6 +498,7 @@ impl Storage {
             }
             FootprintMode::Enforcing => {
                 self.footprint.enforce_access(key, ty, budget)?;
+                self.meter_read(key, budget)?;
                 match self.map.get::<Rc<LedgerKey>>(key, budget)? {
                     Some(None) => Ok(false),
                     Some(Some(_)) => Ok(true),
@@ -293,6 +507,390 @@ impl Storage {
             }
         }
     }
+
+    /// Returns `true` if an entry is currently associated with a given
+    /// [LedgerKey].
+    ///
+    /// Like [Storage::get_expiration], this doesn't record or enforce any
+    /// access in the [Footprint] and is meant to accompany one of the other
+    /// storage operations on the same key.
+    pub fn exists(&self, key: &Rc<LedgerKey>, budget: &Budget) -> Result<bool, HostError> {
+        match self.map.get::<Rc<LedgerKey>>(key, budget)? {
+            Some(entry) => Ok(entry.is_some()),
+            None => match self.mode {
+                FootprintMode::Recording(ref src) => src.has(key),
+                FootprintMode::Enforcing => Ok(false),
+            },
+        }
+    }
+
+    /// Returns the expiration ledger of the entry associated with a given
+    /// [LedgerKey], or `None` if the entry doesn't expire.
+    ///
+    /// This doesn't record or enforce any access in the [Footprint] and is
+    /// meant to accompany one of the other storage operations on the same
+    /// key. In [FootprintMode::Recording] mode, reads through to the
+    /// underlying [SnapshotSource] if the expiration hasn't been loaded yet.
+    pub fn get_expiration(
+        &mut self,
+        key: &Rc<LedgerKey>,
+        budget: &Budget,
+    ) -> Result<Option<u32>, HostError> {
+        if let Some(expiration) = self.expiration_map.get::<Rc<LedgerKey>>(key, budget)? {
+            return Ok(Some(*expiration));
+        }
+        match self.mode {
+            FootprintMode::Recording(ref src) => {
+                // Entries deleted during this run don't have an expiration,
+                // even if the snapshot still holds one.
+                if let Some(None) = self.map.get::<Rc<LedgerKey>>(key, budget)? {
+                    return Ok(None);
+                }
+                let expiration = src.get_expiration(key)?;
+                if let Some(expiration) = expiration {
+                    self.expiration_map =
+                        self.expiration_map
+                            .insert(Rc::clone(key), expiration, budget)?;
+                }
+                Ok(expiration)
+            }
+            FootprintMode::Enforcing => Ok(None),
+        }
+    }
+
+    /// Returns `true` if the entry associated with a given [LedgerKey] has an
+    /// expiration ledger that is earlier than `ledger_seq`.
+    pub fn is_expired(
+        &mut self,
+        key: &Rc<LedgerKey>,
+        ledger_seq: u32,
+        budget: &Budget,
+    ) -> Result<bool, HostError> {
+        Ok(matches!(self.get_expiration(key, budget)?, Some(e) if e < ledger_seq))
+    }
+
+    /// Sets the expiration ledger of the entry associated with a given
+    /// [LedgerKey], regardless of its previous value. This is used for the
+    /// newly created entries and doesn't count as a bump.
+    ///
+    /// Follows the same [Footprint] rules as [Storage::put].
+    pub fn set_expiration(
+        &mut self,
+        key: &Rc<LedgerKey>,
+        expiration: u32,
+        budget: &Budget,
+    ) -> Result<(), HostError> {
+        let ty = AccessType::ReadWrite;
+        match self.mode {
+            FootprintMode::Recording(_) => {
+                self.record_write(key, true, budget)?;
+            }
+            FootprintMode::Enforcing => {
+                self.footprint.enforce_access(key, ty, budget)?;
+            }
+        };
+        self.expiration_map = self
+            .expiration_map
+            .insert(Rc::clone(key), expiration, budget)?;
+        Ok(())
+    }
+
+    /// Removes the expiration ledger of the entry associated with a given
+    /// [LedgerKey], e.g. when the entry itself is deleted.
+    pub fn clear_expiration(
+        &mut self,
+        key: &Rc<LedgerKey>,
+        budget: &Budget,
+    ) -> Result<(), HostError> {
+        if let Some((new_map, _)) = self.expiration_map.remove::<Rc<LedgerKey>>(key, budget)? {
+            self.expiration_map = new_map;
+        }
+        if let Some((new_bumps, _)) = self.bumps.remove::<Rc<LedgerKey>>(key, budget)? {
+            self.bumps = new_bumps;
+        }
+        Ok(())
+    }
+
+    /// Extends the expiration ledger of the entry associated with a given
+    /// [LedgerKey] to at least `expiration`. Entries that don't expire, as
+    /// well as the entries that already live long enough, are not modified.
+    /// Returns the resulting expiration ledger (if any).
+    ///
+    /// Bumping an entry doesn't modify the entry itself, so the [LedgerKey]
+    /// only needs to be accessible as [AccessType::ReadOnly]. Every effective
+    /// bump is recorded in `bumps`.
+    pub fn bump(
+        &mut self,
+        key: &Rc<LedgerKey>,
+        expiration: u32,
+        budget: &Budget,
+    ) -> Result<Option<u32>, HostError> {
+        let ty = AccessType::ReadOnly;
+        match self.mode {
+            FootprintMode::Recording(_) => {
+                self.footprint.record_access(key, ty, budget)?;
+            }
+            FootprintMode::Enforcing => {
+                self.footprint.enforce_access(key, ty, budget)?;
+            }
+        };
+        self.meter_read(key, budget)?;
+        match self.get_expiration(key, budget)? {
+            Some(current) if current < expiration => {
+                self.expiration_map =
+                    self.expiration_map
+                        .insert(Rc::clone(key), expiration, budget)?;
+                self.bumps = self.bumps.insert(Rc::clone(key), expiration, budget)?;
+                Ok(Some(expiration))
+            }
+            res => Ok(res),
+        }
+    }
+
+    /// Charges the ledger IO of reading `key`, unless it has been accessed
+    /// before. Must be called before the [StorageMap] is modified for `key`,
+    /// so that the size charged is the one of the entry before the run.
+    fn meter_read(&mut self, key: &Rc<LedgerKey>, budget: &Budget) -> Result<(), HostError> {
+        if self.read_sizes.contains_key::<Rc<LedgerKey>>(key, budget)? {
+            return Ok(());
+        }
+        let size = match self.map.get::<Rc<LedgerKey>>(key, budget)? {
+            Some(Some(entry)) => entry_xdr_size(entry, budget)?,
+            Some(None) => 0,
+            None => match self.mode {
+                FootprintMode::Recording(ref src) if src.has(key)? => {
+                    entry_xdr_size(&src.get(key)?, budget)?
+                }
+                _ => 0,
+            },
+        };
+        budget.charge_ledger_io(LedgerIoType::ReadEntries, 1)?;
+        budget.charge_ledger_io(LedgerIoType::ReadBytes, size.into())?;
+        self.read_sizes = self.read_sizes.insert(Rc::clone(key), size, budget)?;
+        Ok(())
+    }
+
+    /// Charges the ledger IO of writing `val` under `key`. The entry as it
+    /// was before the run is part of the metadata, so it is charged on the
+    /// first write alongside the write entry.
+    fn meter_write(
+        &mut self,
+        key: &Rc<LedgerKey>,
+        val: Option<&Rc<LedgerEntry>>,
+        budget: &Budget,
+    ) -> Result<(), HostError> {
+        let size = match val {
+            Some(entry) => entry_xdr_size(entry, budget)?,
+            None => 0,
+        };
+        let written = self.write_sizes.get::<Rc<LedgerKey>>(key, budget)?.copied();
+        if written.is_none() {
+            let read = self
+                .read_sizes
+                .get::<Rc<LedgerKey>>(key, budget)?
+                .copied()
+                .unwrap_or(0);
+            budget.charge_ledger_io(LedgerIoType::WriteEntries, 1)?;
+            budget.charge_ledger_io(LedgerIoType::MetadataBytes, read.into())?;
+        }
+        let growth = size.saturating_sub(written.unwrap_or(0));
+        budget.charge_ledger_io(LedgerIoType::WriteBytes, growth.into())?;
+        budget.charge_ledger_io(LedgerIoType::MetadataBytes, growth.into())?;
+        if written.map_or(true, |w| size > w) {
+            self.write_sizes = self.write_sizes.insert(Rc::clone(key), size, budget)?;
+        }
+        Ok(())
+    }
+
+    /// Builds a [FootprintReport] classifying every [LedgerKey] accessed
+    /// during a [FootprintMode::Recording] run against the [SnapshotSource]
+    /// the run was recorded with. Fails in [FootprintMode::Enforcing] mode,
+    /// as there is no snapshot to compare against.
+    ///
+    /// This is meant to be called after the run has finished, e.g. on the
+    /// [Storage] returned from [Host::try_finish](crate::Host::try_finish).
+    pub fn footprint_report(&self, budget: &Budget) -> Result<FootprintReport, HostError> {
+        let src = match self.mode {
+            FootprintMode::Recording(ref src) => src,
+            FootprintMode::Enforcing => {
+                return Err(ScUnknownErrorCode::General.into());
+            }
+        };
+        let mut entries = Vec::with_capacity(self.footprint.0.len());
+        for (key, access) in self.footprint.0.iter(budget)? {
+            let reads = self
+                .read_counts
+                .get::<Rc<LedgerKey>>(key, budget)?
+                .copied()
+                .unwrap_or(0);
+            let reads_before_write = self
+                .reads_before_write
+                .get::<Rc<LedgerKey>>(key, budget)?
+                .copied()
+                .unwrap_or(0);
+            let changing_writes = self
+                .changing_writes
+                .get::<Rc<LedgerKey>>(key, budget)?
+                .copied()
+                .unwrap_or(0);
+            let bump = self.bumps.get::<Rc<LedgerKey>>(key, budget)?.copied();
+            let original = if src.has(key)? {
+                Some(src.get(key)?)
+            } else {
+                None
+            };
+            let kind = match (access, self.map.get::<Rc<LedgerKey>>(key, budget)?) {
+                (AccessType::ReadWrite, Some(current)) => {
+                    // Ledger entries loaded from the snapshot have their
+                    // `last_modified_ledger_seq` set, so only the entry data
+                    // is relevant for detecting no-op writes.
+                    match (&original, current) {
+                        (Some(o), Some(c)) if o.data == c.data => KeyAccessKind::WrittenSameValue,
+                        (None, None) => KeyAccessKind::WrittenSameValue,
+                        (_, Some(_)) => KeyAccessKind::Written,
+                        (Some(_), None) => KeyAccessKind::Deleted,
+                    }
+                }
+                _ => {
+                    if original.is_some() {
+                        KeyAccessKind::ReadHit
+                    } else {
+                        KeyAccessKind::ReadMiss
+                    }
+                }
+            };
+            entries.push(FootprintReportEntry {
+                key: Rc::clone(key),
+                access: access.clone(),
+                kind,
+                reads,
+                reads_before_write,
+                changing_writes,
+                bump,
+            });
+        }
+        Ok(FootprintReport { entries })
+    }
+
+    /// Computes the [LedgerChanges] made during a [FootprintMode::Recording]
+    /// run relative to the [SnapshotSource] the run was recorded with. In
+    /// [FootprintMode::Enforcing] mode the original ledger state has to be
+    /// provided explicitly via [LedgerChanges::from_storage].
+    pub fn ledger_changes(&self, budget: &Budget) -> Result<LedgerChanges, HostError> {
+        match self.mode {
+            FootprintMode::Recording(ref src) => {
+                LedgerChanges::from_storage(self, src.as_ref(), budget)
+            }
+            FootprintMode::Enforcing => Err(ScUnknownErrorCode::General.into()),
+        }
+    }
+}
+
+// Serializing the entry is host work like any other XDR serialization, so it
+// is charged as `ValSer` while being written, like in `Host::metered_write_xdr`.
+fn entry_xdr_size(entry: &LedgerEntry, budget: &Budget) -> Result<u32, HostError> {
+    let mut buf = Vec::new();
+    metered_write_xdr(budget, entry, &mut buf)?;
+    Ok(u32::try_from(buf.len()).unwrap_or(u32::MAX))
+}
+
+fn count_access(
+    counts: &ReadCountMap,
+    key: &Rc<LedgerKey>,
+    budget: &Budget,
+) -> Result<ReadCountMap, HostError> {
+    let count = counts
+        .get::<Rc<LedgerKey>>(key, budget)?
+        .copied()
+        .unwrap_or(0);
+    counts.insert(Rc::clone(key), count.saturating_add(1), budget)
+}
+
+/// The way a single [LedgerKey] has been accessed during a
+/// [FootprintMode::Recording] run, as seen by the [FootprintReport].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum KeyAccessKind {
+    /// The key has only been read and the entry exists in the snapshot.
+    ReadHit,
+    /// The key has only been read and the entry doesn't exist in the
+    /// snapshot.
+    ReadMiss,
+    /// The entry has been created or modified.
+    Written,
+    /// The entry existed in the snapshot and has been deleted.
+    Deleted,
+    /// The entry has been written or deleted, but ended up in the same state
+    /// as in the snapshot. Unless it has been changed in between (or got an
+    /// expiration set), the key only needs [AccessType::ReadOnly] access.
+    WrittenSameValue,
+}
+
+/// A single [LedgerKey] in a [FootprintReport].
+#[derive(Clone, Debug)]
+pub struct FootprintReportEntry {
+    pub key: Rc<LedgerKey>,
+    /// The access recorded in the [Footprint].
+    pub access: AccessType,
+    pub kind: KeyAccessKind,
+    /// Number of times the key has been read.
+    pub reads: u32,
+    /// Number of times the key has been read before its first write.
+    pub reads_before_write: u32,
+    /// Number of writes that changed either the entry or its expiration.
+    pub changing_writes: u32,
+    /// The new expiration ledger if the entry has been bumped.
+    pub bump: Option<u32>,
+}
+
+impl FootprintReportEntry {
+    /// Returns `true` if the key has been read and then written, i.e. its
+    /// access has been upgraded from [AccessType::ReadOnly] to
+    /// [AccessType::ReadWrite].
+    pub fn is_upgraded(&self) -> bool {
+        self.access == AccessType::ReadWrite && self.reads_before_write > 0
+    }
+
+    /// Returns the least permissive [AccessType] sufficient for the key.
+    pub fn minimal_access(&self) -> AccessType {
+        match self.kind {
+            KeyAccessKind::Written | KeyAccessKind::Deleted => AccessType::ReadWrite,
+            KeyAccessKind::WrittenSameValue if self.changing_writes > 0 => AccessType::ReadWrite,
+            KeyAccessKind::WrittenSameValue | KeyAccessKind::ReadHit | KeyAccessKind::ReadMiss => {
+                AccessType::ReadOnly
+            }
+        }
+    }
+}
+
+/// Classification of every [LedgerKey] accessed during a
+/// [FootprintMode::Recording] run, produced by [Storage::footprint_report].
+/// Entries are ordered by [LedgerKey].
+#[derive(Clone, Debug, Default)]
+pub struct FootprintReport {
+    pub entries: Vec<FootprintReportEntry>,
+}
+
+impl FootprintReport {
+    /// Iterates over the entries of the given [KeyAccessKind].
+    pub fn entries_of_kind(
+        &self,
+        kind: KeyAccessKind,
+    ) -> impl Iterator<Item = &FootprintReportEntry> {
+        self.entries.iter().filter(move |e| e.kind == kind)
+    }
+
+    /// Builds the smallest [Footprint] that still allows the recorded run to
+    /// succeed in [FootprintMode::Enforcing] mode. The keys that have only
+    /// been written with the values they already had get
+    /// [AccessType::ReadOnly] access.
+    pub fn minimal_footprint(&self, budget: &Budget) -> Result<Footprint, HostError> {
+        let map = self
+            .entries
+            .iter()
+            .map(|e| (Rc::clone(&e.key), e.minimal_access()))
+            .collect();
+        Ok(Footprint(MeteredOrdMap::from_map(map, budget)?))
+    }
 }
 
 /// A special-purpose map from arbitrary contract-owned values to arbitrary
@@ -441,9 +1039,12 @@ mod test_footprint {
 pub(crate) mod test_storage {
     use std::collections::BTreeMap;
 
-    use soroban_env_common::xdr::ScUnknownErrorCode;
+    use soroban_env_common::xdr::{
+        ContractDataEntry, LedgerEntryData, LedgerKeyContractData, ScStatusType, ScUnknownErrorCode,
+    };
 
     use super::*;
+    use crate::budget::LEDGER_IO_LIMIT_EXCEEDED;
     #[allow(dead_code)]
     pub(crate) struct MockSnapshotSource(BTreeMap<Rc<LedgerKey>, Rc<LedgerEntry>>);
     #[allow(dead_code)]
@@ -465,4 +1066,210 @@ pub(crate) mod test_storage {
             Ok(self.0.contains_key(key))
         }
     }
+
+    fn contract_data(key: i32, val: u32) -> (Rc<LedgerKey>, Rc<LedgerEntry>) {
+        let contract_id: Hash = [0; 32].into();
+        let ledger_key = Rc::new(LedgerKey::ContractData(LedgerKeyContractData {
+            contract_id: contract_id.clone(),
+            key: ScVal::I32(key),
+        }));
+        let entry =
+            Host::ledger_entry_from_data(LedgerEntryData::ContractData(ContractDataEntry {
+                contract_id,
+                key: ScVal::I32(key),
+                val: ScVal::U32(val),
+            }));
+        (ledger_key, entry)
+    }
+
+    #[test]
+    fn footprint_report() -> Result<(), HostError> {
+        let budget = Budget::default();
+        budget.reset_unlimited();
+        let mut snapshot = MockSnapshotSource::new();
+        for k in [0, 2, 3, 4, 5, 6] {
+            let (key, entry) = contract_data(k, 1);
+            snapshot.0.insert(key, entry);
+        }
+        let mut storage = Storage::with_recording_footprint(Rc::new(snapshot));
+
+        let (read_hit, _) = contract_data(0, 1);
+        storage.get(&read_hit, &budget)?;
+        let (read_miss, _) = contract_data(1, 1);
+        assert!(!storage.has(&read_miss, &budget)?);
+        let (written, new_entry) = contract_data(2, 2);
+        storage.get(&written, &budget)?;
+        storage.put(&written, &new_entry, &budget)?;
+        let (same_value, same_entry) = contract_data(3, 1);
+        storage.put(&same_value, &same_entry, &budget)?;
+        let (deleted, _) = contract_data(4, 1);
+        storage.del(&deleted, &budget)?;
+        // Reading after the first write isn't an upgrade.
+        let (written_then_read, new_entry) = contract_data(5, 2);
+        storage.put(&written_then_read, &new_entry, &budget)?;
+        storage.get(&written_then_read, &budget)?;
+        // Changing the entry and restoring it still needs write access.
+        let (restored, new_entry) = contract_data(6, 2);
+        storage.put(&restored, &new_entry, &budget)?;
+        storage.put(&restored, &contract_data(6, 1).1, &budget)?;
+
+        let report = storage.footprint_report(&budget)?;
+        let kinds: Vec<_> = report
+            .entries
+            .iter()
+            .map(|e| (e.kind.clone(), e.is_upgraded()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (KeyAccessKind::ReadHit, false),
+                (KeyAccessKind::ReadMiss, false),
+                (KeyAccessKind::Written, true),
+                (KeyAccessKind::WrittenSameValue, false),
+                (KeyAccessKind::Deleted, false),
+                (KeyAccessKind::Written, false),
+                (KeyAccessKind::WrittenSameValue, false),
+            ]
+        );
+
+        let minimal = report.minimal_footprint(&budget)?;
+        assert_eq!(
+            minimal.0.get::<Rc<LedgerKey>>(&same_value, &budget)?,
+            Some(&AccessType::ReadOnly)
+        );
+        assert_eq!(
+            minimal.0.get::<Rc<LedgerKey>>(&restored, &budget)?,
+            Some(&AccessType::ReadWrite)
+        );
+        assert_eq!(
+            minimal.0.get::<Rc<LedgerKey>>(&read_hit, &budget)?,
+            Some(&AccessType::ReadOnly)
+        );
+        assert_eq!(
+            minimal.0.get::<Rc<LedgerKey>>(&written, &budget)?,
+            Some(&AccessType::ReadWrite)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn minimal_footprint_replays_in_enforcing_mode() -> Result<(), HostError> {
+        let budget = Budget::default();
+        budget.reset_unlimited();
+        let mut snapshot = MockSnapshotSource::new();
+        for k in [0, 2, 3, 4, 5] {
+            let (key, entry) = contract_data(k, 1);
+            snapshot.0.insert(key, entry);
+        }
+        let snapshot = Rc::new(snapshot);
+        let run = |storage: &mut Storage| -> Result<(), HostError> {
+            let (unchanged, same_entry) = contract_data(5, 1);
+            storage.put(&unchanged, &same_entry, &budget)?;
+            storage.del(&contract_data(7, 1).0, &budget)?;
+            storage.get(&contract_data(0, 1).0, &budget)?;
+            storage.has(&contract_data(1, 1).0, &budget)?;
+            let (written, new_entry) = contract_data(2, 2);
+            storage.get(&written, &budget)?;
+            storage.put(&written, &new_entry, &budget)?;
+            let (same_value, same_entry) = contract_data(3, 1);
+            storage.put(&same_value, &same_entry, &budget)?;
+            storage.set_expiration(&same_value, 10, &budget)?;
+            storage.del(&contract_data(4, 1).0, &budget)
+        };
+        let mut storage = Storage::with_recording_footprint(snapshot.clone());
+        run(&mut storage)?;
+        let minimal = storage
+            .footprint_report(&budget)?
+            .minimal_footprint(&budget)?;
+        for k in [5, 7] {
+            assert_eq!(
+                minimal
+                    .0
+                    .get::<Rc<LedgerKey>>(&contract_data(k, 1).0, &budget)?,
+                Some(&AccessType::ReadOnly)
+            );
+        }
+        assert_eq!(
+            minimal
+                .0
+                .get::<Rc<LedgerKey>>(&contract_data(3, 1).0, &budget)?,
+            Some(&AccessType::ReadWrite)
+        );
+
+        let mut map = StorageMap::new()?;
+        for (key, _) in minimal.0.iter(&budget)? {
+            let entry = if snapshot.has(key)? {
+                Some(snapshot.get(key)?)
+            } else {
+                None
+            };
+            map = map.insert(Rc::clone(key), entry, &budget)?;
+        }
+        let mut storage = Storage::with_enforcing_footprint_and_map(minimal, map);
+        run(&mut storage)
+    }
+
+    #[test]
+    fn ledger_io_metering() -> Result<(), HostError> {
+        let budget = Budget::default();
+        budget.reset_unlimited();
+        let mut snapshot = MockSnapshotSource::new();
+        for k in [0, 1] {
+            let (key, entry) = contract_data(k, 1);
+            snapshot.0.insert(key, entry);
+        }
+        let snapshot = Rc::new(snapshot);
+        let mut storage = Storage::with_recording_footprint(snapshot.clone());
+
+        let (read, entry) = contract_data(0, 1);
+        let size = u64::from(entry_xdr_size(&entry, &budget)?);
+        storage.get(&read, &budget)?;
+        storage.get(&read, &budget)?;
+        let (updated, new_entry) = contract_data(1, 2);
+        storage.put(&updated, &new_entry, &budget)?;
+        let (created, new_entry) = contract_data(2, 2);
+        assert!(!storage.has(&created, &budget)?);
+        storage.put(&created, &new_entry, &budget)?;
+        storage.put(&created, &new_entry, &budget)?;
+
+        assert_eq!(budget.get_ledger_io_count(LedgerIoType::ReadEntries), 3);
+        assert_eq!(budget.get_ledger_io_count(LedgerIoType::WriteEntries), 2);
+        assert_eq!(
+            budget.get_ledger_io_count(LedgerIoType::ReadBytes),
+            2 * size
+        );
+        assert_eq!(
+            budget.get_ledger_io_count(LedgerIoType::WriteBytes),
+            2 * size
+        );
+        assert_eq!(
+            budget.get_ledger_io_count(LedgerIoType::MetadataBytes),
+            3 * size
+        );
+        let resources = budget.get_transaction_resources(100);
+        assert_eq!(resources.read_entries, 1);
+        assert_eq!(resources.write_entries, 2);
+        assert_eq!(resources.transaction_size_bytes, 100);
+
+        // Writing more entries than declared fails.
+        budget.reset_ledger_io_limits(&crate::fees::TransactionResources {
+            read_entries: 2,
+            write_entries: 0,
+            ..resources
+        });
+        let mut storage = Storage::with_recording_footprint(snapshot);
+        storage.get(&read, &budget)?;
+        let err = storage.put(&updated, &new_entry, &budget).unwrap_err();
+        assert_eq!(err.status, LEDGER_IO_LIMIT_EXCEEDED.into());
+        assert!(err.status.is_type(ScStatusType::HostStorageError));
+        assert_eq!(err.ledger_io_exceeded(), Some(LedgerIoType::WriteEntries));
+        assert!(err.budget_exceeded().is_none());
+
+        // Resetting the limits of the budget also resets the counts.
+        budget.reset_limits(u64::MAX, u64::MAX);
+        for ty in LedgerIoType::variants() {
+            assert_eq!(budget.get_ledger_io_count(ty), 0);
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,264 @@
+use std::rc::Rc;
+
+use super::{AccessType, MemorySnapshot, SnapshotSource, Storage};
+use crate::budget::Budget;
+use crate::xdr::{LedgerEntry, LedgerKey};
+use crate::HostError;
+
+/// A single change to the ledger state made by a [Storage].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LedgerEntryChange {
+    Created {
+        key: Rc<LedgerKey>,
+        entry: Rc<LedgerEntry>,
+        expiration: Option<u32>,
+    },
+    Updated {
+        key: Rc<LedgerKey>,
+        previous: Rc<LedgerEntry>,
+        entry: Rc<LedgerEntry>,
+        expiration: Option<u32>,
+    },
+    Deleted {
+        key: Rc<LedgerKey>,
+        previous: Rc<LedgerEntry>,
+    },
+    /// The entry itself is unchanged, but its expiration ledger has been
+    /// extended.
+    Bumped { key: Rc<LedgerKey>, expiration: u32 },
+}
+
+impl LedgerEntryChange {
+    pub fn key(&self) -> &Rc<LedgerKey> {
+        match self {
+            LedgerEntryChange::Created { key, .. }
+            | LedgerEntryChange::Updated { key, .. }
+            | LedgerEntryChange::Deleted { key, .. }
+            | LedgerEntryChange::Bumped { key, .. } => key,
+        }
+    }
+}
+
+/// The set of changes a [Storage] makes to the ledger state it has been
+/// running against, ordered by [LedgerKey].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LedgerChanges(pub Vec<LedgerEntryChange>);
+
+impl LedgerChanges {
+    /// Computes the changes made by `storage` to the `original` ledger state,
+    /// i.e. the state the [Storage] has been populated from. Only the keys
+    /// present in the [Storage]'s [Footprint](super::Footprint) are
+    /// considered.
+    pub fn from_storage(
+        storage: &Storage,
+        original: &dyn SnapshotSource,
+        budget: &Budget,
+    ) -> Result<Self, HostError> {
+        let mut changes = vec![];
+        for (key, access) in storage.footprint.0.iter(budget)? {
+            let original_entry = if original.has(key)? {
+                Some(original.get(key)?)
+            } else {
+                None
+            };
+            let new_expiration = storage
+                .expiration_map
+                .get::<Rc<LedgerKey>>(key, budget)?
+                .copied();
+            // Expirations are only loaded into the storage when they are
+            // looked up, so an entry without one there keeps the original.
+            let expiration = match new_expiration {
+                Some(e) => Some(e),
+                None if original_entry.is_some() => original.get_expiration(key)?,
+                None => None,
+            };
+            let current_entry = match access {
+                AccessType::ReadWrite => storage.map.get::<Rc<LedgerKey>>(key, budget)?,
+                AccessType::ReadOnly => None,
+            };
+            let change = match (original_entry, current_entry) {
+                (None, Some(Some(entry))) => Some(LedgerEntryChange::Created {
+                    key: Rc::clone(key),
+                    entry: Rc::clone(entry),
+                    expiration,
+                }),
+                (Some(previous), Some(Some(entry))) if previous.data != entry.data => {
+                    Some(LedgerEntryChange::Updated {
+                        key: Rc::clone(key),
+                        previous,
+                        entry: Rc::clone(entry),
+                        expiration,
+                    })
+                }
+                (Some(previous), Some(None)) => Some(LedgerEntryChange::Deleted {
+                    key: Rc::clone(key),
+                    previous,
+                }),
+                (Some(_), _) => match new_expiration {
+                    Some(e) if Some(e) != original.get_expiration(key)? => {
+                        Some(LedgerEntryChange::Bumped {
+                            key: Rc::clone(key),
+                            expiration: e,
+                        })
+                    }
+                    _ => None,
+                },
+                (None, _) => None,
+            };
+            if let Some(change) = change {
+                changes.push(change);
+            }
+        }
+        Ok(LedgerChanges(changes))
+    }
+
+    pub fn created(&self) -> impl Iterator<Item = &LedgerEntryChange> {
+        self.0
+            .iter()
+            .filter(|c| matches!(c, LedgerEntryChange::Created { .. }))
+    }
+
+    pub fn updated(&self) -> impl Iterator<Item = &LedgerEntryChange> {
+        self.0
+            .iter()
+            .filter(|c| matches!(c, LedgerEntryChange::Updated { .. }))
+    }
+
+    pub fn deleted(&self) -> impl Iterator<Item = &LedgerEntryChange> {
+        self.0
+            .iter()
+            .filter(|c| matches!(c, LedgerEntryChange::Deleted { .. }))
+    }
+
+    /// Applies the changes to a [MemorySnapshot], so that it can serve as the
+    /// ledger state for the next transaction. Updated entries without an
+    /// expiration keep the one they have in the snapshot.
+    pub fn apply_to(&self, snapshot: &mut MemorySnapshot) -> Result<(), HostError> {
+        for change in self.0.iter() {
+            match change {
+                LedgerEntryChange::Created {
+                    entry, expiration, ..
+                } => snapshot.insert(Rc::clone(entry), *expiration)?,
+                LedgerEntryChange::Updated {
+                    key,
+                    entry,
+                    expiration,
+                    ..
+                } => {
+                    let expiration = match expiration {
+                        Some(e) => Some(*e),
+                        None => snapshot.get_expiration(key)?,
+                    };
+                    snapshot.insert(Rc::clone(entry), expiration)?
+                }
+                LedgerEntryChange::Deleted { key, .. } => {
+                    snapshot.remove(key);
+                }
+                LedgerEntryChange::Bumped { key, expiration } => {
+                    if let Some((entry, _)) = snapshot.remove(key) {
+                        snapshot.insert(entry, Some(*expiration))?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::storage::ledger_key_from_entry;
+    use crate::xdr::{ContractDataEntry, Hash, LedgerEntryData, LedgerEntryExt, ScVal};
+
+    fn contract_data_entry(key: i32, val: i32) -> Rc<LedgerEntry> {
+        Rc::new(LedgerEntry {
+            last_modified_ledger_seq: 0,
+            data: LedgerEntryData::ContractData(ContractDataEntry {
+                contract_id: Hash([1; 32]),
+                key: ScVal::I32(key),
+                val: ScVal::I32(val),
+            }),
+            ext: LedgerEntryExt::V0,
+        })
+    }
+
+    fn run(
+        snapshot: &MemorySnapshot,
+        f: impl FnOnce(&mut Storage) -> Result<(), HostError>,
+    ) -> Result<LedgerChanges, HostError> {
+        let budget = Budget::default();
+        budget.reset_unlimited();
+        let mut storage = Storage::with_recording_footprint(Rc::new(snapshot.clone()));
+        f(&mut storage)?;
+        storage.ledger_changes(&budget)
+    }
+
+    #[test]
+    fn chain_ledger_changes() -> Result<(), HostError> {
+        let budget = Budget::default();
+        budget.reset_unlimited();
+        let mut snapshot = MemorySnapshot::new();
+        snapshot.insert(contract_data_entry(0, 0), None)?;
+        snapshot.insert(contract_data_entry(1, 0), None)?;
+        let key = |k| -> Result<Rc<LedgerKey>, HostError> {
+            Ok(Rc::new(ledger_key_from_entry(&contract_data_entry(k, 0))?))
+        };
+
+        let changes = run(&snapshot, |storage| {
+            storage.get(&key(0)?, &budget)?;
+            storage.put(&key(1)?, &contract_data_entry(1, 1), &budget)?;
+            storage.put(&key(2)?, &contract_data_entry(2, 2), &budget)
+        })?;
+        assert_eq!(changes.0.len(), 2);
+        assert_eq!(changes.created().count(), 1);
+        assert_eq!(changes.updated().count(), 1);
+        changes.apply_to(&mut snapshot)?;
+        assert_eq!(snapshot.len(), 3);
+
+        // The next transaction sees the state left by the previous one.
+        let changes = run(&snapshot, |storage| {
+            assert_eq!(storage.get(&key(2)?, &budget)?, contract_data_entry(2, 2));
+            storage.del(&key(0)?, &budget)
+        })?;
+        assert_eq!(changes.deleted().count(), 1);
+        changes.apply_to(&mut snapshot)?;
+        assert!(!snapshot.has(&key(0)?)?);
+        Ok(())
+    }
+    #[test]
+    fn updated_entry_keeps_expiration() -> Result<(), HostError> {
+        let budget = Budget::default();
+        budget.reset_unlimited();
+        let mut snapshot = MemorySnapshot::new();
+        snapshot.insert(contract_data_entry(0, 0), Some(100))?;
+        let key = Rc::new(ledger_key_from_entry(&contract_data_entry(0, 0))?);
+
+        let changes = run(&snapshot, |storage| {
+            storage.put(&key, &contract_data_entry(0, 1), &budget)
+        })?;
+        assert_eq!(
+            changes.0,
+            vec![LedgerEntryChange::Updated {
+                key: Rc::clone(&key),
+                previous: contract_data_entry(0, 0),
+                entry: contract_data_entry(0, 1),
+                expiration: Some(100),
+            }]
+        );
+        changes.apply_to(&mut snapshot)?;
+        assert_eq!(snapshot.get(&key)?, contract_data_entry(0, 1));
+        assert_eq!(snapshot.get_expiration(&key)?, Some(100));
+
+        // Changes built without the expiration don't drop it either.
+        let changes = LedgerChanges(vec![LedgerEntryChange::Updated {
+            key: Rc::clone(&key),
+            previous: contract_data_entry(0, 1),
+            entry: contract_data_entry(0, 2),
+            expiration: None,
+        }]);
+        changes.apply_to(&mut snapshot)?;
+        assert_eq!(snapshot.get_expiration(&key)?, Some(100));
+        Ok(())
+    }
+}
@@ -0,0 +1,343 @@
+//! Ready-made [SnapshotSource] implementations.
+//!
+//!   - [MemorySnapshot] keeps the ledger entries in memory and can be loaded
+//!     from and saved to a stream of XDR [LedgerEntry] records.
+//!   - [LayeredSnapshot] overlays the entries of a [Storage] on top of some
+//!     base [SnapshotSource], which allows chaining multiple simulated
+//!     transactions without applying their changes anywhere.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::rc::Rc;
+
+use super::{ExpirationMap, SnapshotSource, Storage, StorageMap};
+use crate::budget::Budget;
+use crate::xdr::{
+    self, LedgerEntry, LedgerEntryData, LedgerKey, LedgerKeyAccount, LedgerKeyClaimableBalance,
+    LedgerKeyContractCode, LedgerKeyContractData, LedgerKeyData, LedgerKeyLiquidityPool,
+    LedgerKeyOffer, LedgerKeyTrustLine, ReadXdr, ScHostStorageErrorCode, ScUnknownErrorCode,
+    WriteXdr,
+};
+use crate::HostError;
+
+/// Derives the [LedgerKey] that a given [LedgerEntry] is stored under.
+pub fn ledger_key_from_entry(entry: &LedgerEntry) -> Result<LedgerKey, HostError> {
+    let key = match &entry.data {
+        LedgerEntryData::Account(e) => LedgerKey::Account(LedgerKeyAccount {
+            account_id: e.account_id.clone(),
+        }),
+        LedgerEntryData::Trustline(e) => LedgerKey::Trustline(LedgerKeyTrustLine {
+            account_id: e.account_id.clone(),
+            asset: e.asset.clone(),
+        }),
+        LedgerEntryData::Offer(e) => LedgerKey::Offer(LedgerKeyOffer {
+            seller_id: e.seller_id.clone(),
+            offer_id: e.offer_id,
+        }),
+        LedgerEntryData::Data(e) => LedgerKey::Data(LedgerKeyData {
+            account_id: e.account_id.clone(),
+            data_name: e.data_name.clone(),
+        }),
+        LedgerEntryData::ClaimableBalance(e) => {
+            LedgerKey::ClaimableBalance(LedgerKeyClaimableBalance {
+                balance_id: e.balance_id.clone(),
+            })
+        }
+        LedgerEntryData::LiquidityPool(e) => LedgerKey::LiquidityPool(LedgerKeyLiquidityPool {
+            liquidity_pool_id: e.liquidity_pool_id.clone(),
+        }),
+        LedgerEntryData::ContractData(e) => LedgerKey::ContractData(LedgerKeyContractData {
+            contract_id: e.contract_id.clone(),
+            key: e.key.clone(),
+        }),
+        LedgerEntryData::ContractCode(e) => LedgerKey::ContractCode(LedgerKeyContractCode {
+            hash: e.hash.clone(),
+        }),
+        // Config settings are never accessed by contracts and are not
+        // supported in snapshots.
+        LedgerEntryData::ConfigSetting(_) => return Err(ScUnknownErrorCode::General.into()),
+    };
+    Ok(key)
+}
+
+/// The encoding of a file holding a [MemorySnapshot].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SnapshotFileFormat {
+    /// A plain concatenation of XDR-encoded [LedgerEntry] records, each
+    /// followed by its XDR-encoded optional expiration ledger (`uint32*`).
+    Binary,
+    /// One base64-encoded XDR [LedgerEntry] record per line, followed by a
+    /// space and the decimal expiration ledger for the entries that have one.
+    Base64,
+}
+
+/// An in-memory [SnapshotSource] holding the ledger entries in a [BTreeMap],
+/// along with their optional expiration ledgers.
+#[derive(Clone, Default)]
+pub struct MemorySnapshot {
+    entries: BTreeMap<Rc<LedgerKey>, (Rc<LedgerEntry>, Option<u32>)>,
+}
+
+impl MemorySnapshot {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a snapshot from the given non-expiring entries.
+    pub fn from_entries<I>(entries: I) -> Result<Self, HostError>
+    where
+        I: IntoIterator<Item = LedgerEntry>,
+    {
+        let mut snapshot = Self::new();
+        for entry in entries {
+            snapshot.insert(Rc::new(entry), None)?;
+        }
+        Ok(snapshot)
+    }
+
+    /// Inserts an entry with an optional expiration ledger, replacing the
+    /// existing entry under the same [LedgerKey] (if any).
+    pub fn insert(
+        &mut self,
+        entry: Rc<LedgerEntry>,
+        expiration: Option<u32>,
+    ) -> Result<(), HostError> {
+        let key = Rc::new(ledger_key_from_entry(&entry)?);
+        self.entries.insert(key, (entry, expiration));
+        Ok(())
+    }
+
+    /// Removes the entry under the given [LedgerKey], returning it.
+    pub fn remove(&mut self, key: &LedgerKey) -> Option<(Rc<LedgerEntry>, Option<u32>)> {
+        self.entries.remove(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterates over the entries in the [LedgerKey] order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Rc<LedgerKey>, &(Rc<LedgerEntry>, Option<u32>))> {
+        self.entries.iter()
+    }
+
+    /// Reads the entries and their expiration ledgers from a stream in the
+    /// given [SnapshotFileFormat].
+    pub fn read_from(r: impl Read, format: SnapshotFileFormat) -> Result<Self, HostError> {
+        let mut r = BufReader::new(r);
+        let mut snapshot = Self::new();
+        match format {
+            SnapshotFileFormat::Binary => {
+                while !r.fill_buf().map_err(xdr::Error::Io)?.is_empty() {
+                    let entry = LedgerEntry::read_xdr(&mut r)?;
+                    let expiration = Option::<u32>::read_xdr(&mut r)?;
+                    snapshot.insert(Rc::new(entry), expiration)?;
+                }
+            }
+            SnapshotFileFormat::Base64 => {
+                for line in r.lines() {
+                    let line = line.map_err(xdr::Error::Io)?;
+                    let mut fields = line.split_whitespace();
+                    let entry = match fields.next() {
+                        Some(entry) => LedgerEntry::from_xdr_base64(entry.to_string())?,
+                        None => continue,
+                    };
+                    let expiration = fields
+                        .next()
+                        .map(|e| e.parse::<u32>().map_err(|_| xdr::Error::Invalid))
+                        .transpose()?;
+                    if fields.next().is_some() {
+                        return Err(xdr::Error::Invalid.into());
+                    }
+                    snapshot.insert(Rc::new(entry), expiration)?;
+                }
+            }
+        }
+        Ok(snapshot)
+    }
+
+    /// Writes the entries and their expiration ledgers to a stream in the
+    /// given [SnapshotFileFormat].
+    pub fn write_to(&self, w: impl Write, format: SnapshotFileFormat) -> Result<(), HostError> {
+        let mut w = BufWriter::new(w);
+        for (entry, expiration) in self.entries.values() {
+            match format {
+                SnapshotFileFormat::Binary => {
+                    entry.write_xdr(&mut w)?;
+                    expiration.write_xdr(&mut w)?;
+                }
+                SnapshotFileFormat::Base64 => {
+                    write!(w, "{}", entry.to_xdr_base64()?).map_err(xdr::Error::Io)?;
+                    if let Some(expiration) = expiration {
+                        write!(w, " {}", expiration).map_err(xdr::Error::Io)?;
+                    }
+                    writeln!(w).map_err(xdr::Error::Io)?;
+                }
+            }
+        }
+        w.flush().map_err(xdr::Error::Io)?;
+        Ok(())
+    }
+
+    pub fn load_file(
+        path: impl AsRef<Path>,
+        format: SnapshotFileFormat,
+    ) -> Result<Self, HostError> {
+        let file = File::open(path).map_err(xdr::Error::Io)?;
+        Self::read_from(file, format)
+    }
+
+    pub fn save_file(
+        &self,
+        path: impl AsRef<Path>,
+        format: SnapshotFileFormat,
+    ) -> Result<(), HostError> {
+        let file = File::create(path).map_err(xdr::Error::Io)?;
+        self.write_to(file, format)
+    }
+}
+
+impl SnapshotSource for MemorySnapshot {
+    fn get(&self, key: &Rc<LedgerKey>) -> Result<Rc<LedgerEntry>, HostError> {
+        match self.entries.get(key) {
+            Some((entry, _)) => Ok(Rc::clone(entry)),
+            None => Err(ScHostStorageErrorCode::MissingKeyInGet.into()),
+        }
+    }
+
+    fn has(&self, key: &Rc<LedgerKey>) -> Result<bool, HostError> {
+        Ok(self.entries.contains_key(key))
+    }
+
+    fn get_expiration(&self, key: &Rc<LedgerKey>) -> Result<Option<u32>, HostError> {
+        Ok(self
+            .entries
+            .get(key)
+            .and_then(|(_, expiration)| *expiration))
+    }
+}
+
+/// A [SnapshotSource] that serves the entries written (or deleted) in a
+/// [Storage] and falls back to a base [SnapshotSource] for everything else.
+///
+/// Since [Storage] maps are metered, the lookups into the overlay are
+/// charged to the provided [Budget]; usually this would be an unlimited
+/// budget that is not associated with any [Host](crate::Host).
+#[derive(Clone)]
+pub struct LayeredSnapshot {
+    base: Rc<dyn SnapshotSource>,
+    map: StorageMap,
+    expiration_map: ExpirationMap,
+    budget: Budget,
+}
+
+impl LayeredSnapshot {
+    pub fn new(base: Rc<dyn SnapshotSource>, storage: &Storage, budget: Budget) -> Self {
+        Self {
+            base,
+            map: storage.map.clone(),
+            expiration_map: storage.expiration_map.clone(),
+            budget,
+        }
+    }
+}
+
+impl SnapshotSource for LayeredSnapshot {
+    fn get(&self, key: &Rc<LedgerKey>) -> Result<Rc<LedgerEntry>, HostError> {
+        match self.map.get::<Rc<LedgerKey>>(key, &self.budget)? {
+            Some(Some(entry)) => Ok(Rc::clone(entry)),
+            Some(None) => Err(ScHostStorageErrorCode::GetOnDeletedKey.into()),
+            None => self.base.get(key),
+        }
+    }
+
+    fn has(&self, key: &Rc<LedgerKey>) -> Result<bool, HostError> {
+        match self.map.get::<Rc<LedgerKey>>(key, &self.budget)? {
+            Some(entry) => Ok(entry.is_some()),
+            None => self.base.has(key),
+        }
+    }
+
+    fn get_expiration(&self, key: &Rc<LedgerKey>) -> Result<Option<u32>, HostError> {
+        if let Some(expiration) = self
+            .expiration_map
+            .get::<Rc<LedgerKey>>(key, &self.budget)?
+        {
+            return Ok(Some(*expiration));
+        }
+        match self.map.get::<Rc<LedgerKey>>(key, &self.budget)? {
+            Some(None) => Ok(None),
+            _ => self.base.get_expiration(key),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::xdr::{ContractDataEntry, Hash, LedgerEntryExt, ScVal};
+
+    fn contract_data_entry(key: i32, val: i32) -> LedgerEntry {
+        LedgerEntry {
+            last_modified_ledger_seq: 1,
+            data: LedgerEntryData::ContractData(ContractDataEntry {
+                contract_id: Hash([1; 32]),
+                key: ScVal::I32(key),
+                val: ScVal::I32(val),
+            }),
+            ext: LedgerEntryExt::V0,
+        }
+    }
+
+    #[test]
+    fn memory_snapshot_roundtrip() -> Result<(), HostError> {
+        let mut snapshot =
+            MemorySnapshot::from_entries((0..3).map(|i| contract_data_entry(i, i * 10)))?;
+        snapshot.insert(Rc::new(contract_data_entry(3, 30)), Some(0))?;
+        snapshot.insert(Rc::new(contract_data_entry(4, 40)), Some(u32::MAX))?;
+        for format in [SnapshotFileFormat::Binary, SnapshotFileFormat::Base64] {
+            let mut buf = vec![];
+            snapshot.write_to(&mut buf, format)?;
+            let loaded = MemorySnapshot::read_from(buf.as_slice(), format)?;
+            assert_eq!(loaded.len(), 5);
+            for (key, (entry, expiration)) in snapshot.iter() {
+                assert_eq!(loaded.get(key)?, *entry);
+                assert_eq!(loaded.get_expiration(key)?, *expiration);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn layered_snapshot_overlays_storage() -> Result<(), HostError> {
+        let budget = Budget::default();
+        budget.reset_unlimited();
+        let mut base = MemorySnapshot::new();
+        base.insert(Rc::new(contract_data_entry(0, 0)), Some(100))?;
+        base.insert(Rc::new(contract_data_entry(1, 0)), None)?;
+        let base: Rc<dyn SnapshotSource> = Rc::new(base);
+
+        let mut storage = Storage::with_recording_footprint(Rc::clone(&base));
+        let updated = Rc::new(contract_data_entry(0, 5));
+        let key_0 = Rc::new(ledger_key_from_entry(&updated)?);
+        storage.put(&key_0, &updated, &budget)?;
+        let key_1 = Rc::new(ledger_key_from_entry(&contract_data_entry(1, 0))?);
+        storage.del(&key_1, &budget)?;
+        let created = Rc::new(contract_data_entry(2, 0));
+        let key_2 = Rc::new(ledger_key_from_entry(&created)?);
+        storage.put(&key_2, &created, &budget)?;
+
+        let layered = LayeredSnapshot::new(base, &storage, budget);
+        assert_eq!(layered.get(&key_0)?, updated);
+        assert_eq!(layered.get_expiration(&key_0)?, Some(100));
+        assert!(!layered.has(&key_1)?);
+        assert!(layered.has(&key_2)?);
+        Ok(())
+    }
+}
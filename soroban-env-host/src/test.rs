@@ -4,6 +4,7 @@ mod address;
 mod basic;
 mod bytes;
 mod crypto;
+mod fees;
 mod ledger;
 mod map;
 mod num;
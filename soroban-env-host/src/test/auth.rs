@@ -1292,3 +1292,229 @@ fn test_out_of_order_auth() {
         false,
     );
 }
+
+#[test]
+fn test_invoker_contract_sub_invocation_auth() {
+    use crate::native_contract::invoker_contract_auth::{
+        SubContractInvocation, MAX_SUB_CONTRACT_INVOCATION_DEPTH,
+    };
+    use crate::xdr::{ScHostAuthErrorCode, ScHostFnErrorCode};
+    use crate::HostError;
+    use soroban_env_common::RawVal;
+
+    let host = Host::test_host();
+    let [vault, amm, token, router] = [[1_u8; 32], [2; 32], [3; 32], [4; 32]];
+    let vault_address = host
+        .contract_id_to_address(BytesN::<32>::from_slice(&host, &vault).unwrap().into())
+        .unwrap();
+    let call = |id: [u8; 32], f: &str, inner: &dyn Fn() -> Result<RawVal, HostError>| {
+        host.with_test_contract_frame(Hash(id), Symbol::try_from_small_str(f).unwrap(), inner)
+    };
+    let authorize_transfer = || {
+        let transfer = SubContractInvocation {
+            contract: BytesN::from_slice(&host, &token).unwrap(),
+            fn_name: Symbol::try_from_small_str("transfer").unwrap(),
+            args: HostVec::new(&host).unwrap(),
+            sub_invocations: HostVec::new(&host).unwrap(),
+        };
+        let mut entries = HostVec::new(&host).unwrap();
+        entries.push(&transfer).unwrap();
+        host.authorize_as_curr_contract(entries.into()).unwrap();
+    };
+    let require_vault_auth = || host.require_auth(vault_address);
+
+    // The vault can't be authorized for the nested `transfer` by default.
+    let res = call(vault, "deposit", &|| {
+        call(amm, "swap", &|| {
+            call(token, "transfer", &require_vault_auth)
+        })
+    });
+    assert!(HostError::result_matches_err_status(
+        res,
+        ScHostAuthErrorCode::NotAuthorized
+    ));
+
+    // Once authorized, the `transfer` can be called only once.
+    let res = call(vault, "deposit", &|| {
+        authorize_transfer();
+        call(amm, "swap", &|| {
+            // The direct call is still authorized by default.
+            require_vault_auth()?;
+            call(token, "transfer", &require_vault_auth)?;
+            call(token, "transfer", &require_vault_auth)
+        })
+    });
+    assert!(HostError::result_matches_err_status(
+        res,
+        ScHostAuthErrorCode::NotAuthorized
+    ));
+    call(vault, "deposit", &|| {
+        authorize_transfer();
+        call(amm, "swap", &|| {
+            call(token, "transfer", &require_vault_auth)
+        })
+    })
+    .unwrap();
+
+    // Direct calls don't consume the authorizations, even when they match.
+    call(vault, "deposit", &|| {
+        authorize_transfer();
+        call(token, "transfer", &require_vault_auth)?;
+        call(amm, "swap", &|| {
+            call(token, "transfer", &require_vault_auth)
+        })
+    })
+    .unwrap();
+
+    // The authorization expires together with the vault frame.
+    let res = call(router, "route", &|| {
+        call(vault, "prepare", &|| {
+            authorize_transfer();
+            Ok(RawVal::VOID.into())
+        })?;
+        call(vault, "deposit", &|| {
+            call(amm, "swap", &|| {
+                call(token, "transfer", &require_vault_auth)
+            })
+        })
+    });
+    assert!(HostError::result_matches_err_status(
+        res,
+        ScHostAuthErrorCode::NotAuthorized
+    ));
+
+    // The depth of the authorized invocation trees is limited.
+    let nested_transfers = |depth: u32| {
+        let mut sub_invocations = HostVec::new(&host).unwrap();
+        for _ in 0..depth {
+            let transfer = SubContractInvocation {
+                contract: BytesN::from_slice(&host, &token).unwrap(),
+                fn_name: Symbol::try_from_small_str("transfer").unwrap(),
+                args: HostVec::new(&host).unwrap(),
+                sub_invocations,
+            };
+            sub_invocations = HostVec::new(&host).unwrap();
+            sub_invocations.push(&transfer).unwrap();
+        }
+        sub_invocations
+    };
+    call(vault, "deposit", &|| {
+        host.authorize_as_curr_contract(
+            nested_transfers(MAX_SUB_CONTRACT_INVOCATION_DEPTH).into(),
+        )?;
+        Ok(RawVal::VOID.into())
+    })
+    .unwrap();
+    let res = call(vault, "deposit", &|| {
+        host.authorize_as_curr_contract(
+            nested_transfers(MAX_SUB_CONTRACT_INVOCATION_DEPTH + 1).into(),
+        )?;
+        Ok(RawVal::VOID.into())
+    });
+    assert!(HostError::result_matches_err_status(
+        res,
+        ScHostFnErrorCode::InputArgsInvalid
+    ));
+}
+
+#[test]
+fn test_expiring_authorization() {
+    use crate::auth::{expiring_signature_payload_preimage, ExpiringContractAuth};
+    use crate::xdr::WriteXdr;
+
+    let test = AuthTest::setup(1, 1);
+    let set_ledger_seq = |sequence_number| {
+        test.host.set_ledger_info(LedgerInfo {
+            sequence_number,
+            max_entry_expiration: 1000,
+            ..Default::default()
+        })
+    };
+    let setup = SetupNode::new(&test.contracts[0], vec![true], vec![]);
+    let root_invocation = test.convert_sign_node(&SignNode::tree_fn(&test.contracts[0], vec![]));
+    let auth_entry = |nonce: u64, signed_expiration: u32, expiration: u32| {
+        let payload_preimage = HashIdPreimage::ContractAuth(HashIdPreimageContractAuth {
+            network_id: Hash([0; 32]),
+            invocation: root_invocation.clone(),
+            nonce,
+        });
+        let payload = test
+            .host
+            .sha256_hash_from_bytes(
+                &expiring_signature_payload_preimage(
+                    test.host.metered_hash_xdr(&payload_preimage).unwrap(),
+                    signed_expiration,
+                )
+                .unwrap()
+                .to_xdr()
+                .unwrap(),
+            )
+            .unwrap();
+        let signature_args = host_vec![
+            &test.host,
+            host_vec![
+                &test.host,
+                sign_payload_for_account(&test.host, &test.keys[0], &payload)
+            ]
+        ];
+        ExpiringContractAuth {
+            contract_auth: ContractAuth {
+                address_with_nonce: Some(AddressWithNonce {
+                    address: test.key_to_sc_address(&test.keys[0]),
+                    nonce,
+                }),
+                root_invocation: root_invocation.clone(),
+                signature_args: test.host.call_args_to_scvec(signature_args.into()).unwrap(),
+            },
+            signature_expiration_ledger: expiration,
+        }
+    };
+    let call = |entries: Vec<ExpiringContractAuth>| {
+        test.host
+            .set_expiring_authorization_entries(entries)
+            .unwrap();
+        test.host
+            .call(
+                test.contracts[0].clone().into(),
+                Symbol::try_from_small_str("tree_fn").unwrap(),
+                host_vec![
+                    &test.host,
+                    test.get_addresses(),
+                    test.convert_setup_tree(&setup)
+                ]
+                .into(),
+            )
+            .is_ok()
+    };
+
+    set_ledger_seq(100);
+    // Nonces don't have to be sequential.
+    assert!(call(vec![auth_entry(42, 110, 110)]));
+    assert!(call(vec![auth_entry(7, 110, 110)]));
+    // But can't be reused while the signature is valid.
+    assert!(!call(vec![auth_entry(42, 110, 110)]));
+    assert!(!call(vec![auth_entry(42, 120, 120)]));
+    // The expiration ledger is a part of the signature payload.
+    assert!(!call(vec![auth_entry(1, 110, 120)]));
+    // The signature can't outlive the maximum entry expiration.
+    assert!(!call(vec![auth_entry(2, 1101, 1101)]));
+    assert!(call(vec![auth_entry(2, 1100, 1100)]));
+
+    set_ledger_seq(111);
+    // Expired signatures are rejected.
+    assert!(!call(vec![auth_entry(3, 110, 110)]));
+    // The nonce becomes available again once the signatures that could have
+    // used it have expired.
+    assert!(call(vec![auth_entry(42, 120, 120)]));
+
+    // The sequential nonce hasn't been touched.
+    assert_eq!(
+        test.host
+            .read_nonce(
+                &Hash(test.contracts[0].to_array().unwrap()),
+                &test.key_to_sc_address(&test.keys[0])
+            )
+            .unwrap(),
+        0
+    );
+}
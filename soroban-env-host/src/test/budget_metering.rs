@@ -1,11 +1,15 @@
 use crate::{
-    budget::AsBudget,
+    budget::{AsBudget, Budget, CostType, HostCostType},
+    events::Event,
     host::metered_clone::MeteredClone,
-    xdr::{ContractCostType, ScMap, ScMapEntry, ScVal, ScVmErrorCode},
-    Env, Host, HostError, RawVal, Symbol,
+    xdr::{
+        ContractCostParamEntry, ContractCostParams, ContractCostType, ExtensionPoint, Hash, ScMap,
+        ScMapEntry, ScVal, ScVmErrorCode,
+    },
+    DiagnosticLevel, Env, Host, HostError, ModuleCache, RawVal, Symbol,
 };
 use expect_test::{self, expect};
-use soroban_test_wasms::VEC;
+use soroban_test_wasms::{ADD_I32, VEC};
 
 #[test]
 fn xdr_object_conversion() -> Result<(), HostError> {
@@ -69,6 +73,167 @@ fn vm_hostfn_invocation() -> Result<(), HostError> {
     Ok(())
 }
 
+#[test]
+fn vm_module_cache() -> Result<(), HostError> {
+    let cache = ModuleCache::new();
+    let sym = Symbol::try_from_small_str("vec_err").unwrap();
+    let run = |cache: Option<&ModuleCache>| -> Result<_, HostError> {
+        let host = Host::test_host_with_recording_footprint();
+        if let Some(cache) = cache {
+            host.set_module_cache(cache.clone());
+        }
+        let id_obj = host.register_test_contract_wasm(VEC)?;
+        let host = host.test_budget(100_000, 100_000);
+        let args = host.test_vec_obj::<u32>(&[1])?;
+        host.try_call(id_obj, sym.into(), args.clone().into())?;
+        host.try_call(id_obj, sym.into(), args.into())?;
+        Ok(host.with_budget(|budget| {
+            (
+                budget.get_tracker(ContractCostType::VmInstantiation),
+                budget.get_cpu_insns_count(),
+                budget.get_mem_bytes_count(),
+            )
+        }))
+    };
+
+    // The instantiations are charged the same whether the module is parsed,
+    // added to the cache or taken out of it.
+    let uncached = run(None)?;
+    assert_eq!(uncached.0, (2, Some(2 * VEC.len() as u64)));
+    for _ in 0..2 {
+        assert_eq!(run(Some(&cache))?, uncached);
+        assert_eq!(cache.len(), 1);
+    }
+    Ok(())
+}
+
+#[test]
+fn vm_reuse() -> Result<(), HostError> {
+    let sym = Symbol::try_from_small_str("add").unwrap();
+    let mut costs = vec![];
+    for reuse in [false, true] {
+        let host = Host::test_host_with_recording_footprint();
+        let id_obj = host.register_test_contract_wasm(ADD_I32)?;
+        let id = host.hash_from_bytesobj_input("contract_id", id_obj)?;
+        host.set_vm_reuse(id.clone(), reuse);
+        host.with_budget(|budget| budget.reset_unlimited());
+        let args = host.test_vec_obj::<i32>(&[1, 2])?;
+        let mut call_cpu_insns = vec![];
+        host.with_test_contract_frame([1; 32].into(), Symbol::from_small_str("router"), || {
+            for _ in 0..3 {
+                let before = host.with_budget(|budget| budget.get_cpu_insns_count());
+                host.call(id_obj, sym.into(), args.into())?;
+                call_cpu_insns
+                    .push(host.with_budget(|budget| budget.get_cpu_insns_count()) - before);
+            }
+            assert_eq!(host.0.reusable_vms.borrow().contains_key(&id), reuse);
+            Ok(RawVal::VOID.into())
+        })?;
+
+        // Nothing is kept once the invocation has finished.
+        assert!(host.0.reusable_vms.borrow().is_empty());
+        costs.push(host.with_budget(|budget| {
+            (
+                budget.get_tracker(ContractCostType::VmInstantiation).0,
+                budget.get_tracker(ContractCostType::VmMemRead).0,
+                call_cpu_insns,
+            )
+        }));
+    }
+    let (instantiations, _, call_cpu_insns) = &costs[0];
+    assert_eq!(*instantiations, 3);
+    assert_eq!(call_cpu_insns[1], call_cpu_insns[0]);
+
+    // The reused instances are only charged for the access, which makes the
+    // calls after the first one cheaper.
+    let (instantiations, mem_reads, reuse_call_cpu_insns) = &costs[1];
+    assert_eq!(*instantiations, 1);
+    assert_eq!(*mem_reads, costs[0].1 + 2);
+    assert_eq!(reuse_call_cpu_insns[0], call_cpu_insns[0]);
+    assert!(reuse_call_cpu_insns[1] < reuse_call_cpu_insns[0]);
+    assert_eq!(reuse_call_cpu_insns[2], reuse_call_cpu_insns[1]);
+    Ok(())
+}
+
+#[test]
+fn vm_reuse_after_rollback() -> Result<(), HostError> {
+    let sym = Symbol::try_from_small_str("add").unwrap();
+    let host = Host::test_host_with_recording_footprint();
+    let id_obj = host.register_test_contract_wasm(ADD_I32)?;
+    let id = host.hash_from_bytesobj_input("contract_id", id_obj)?;
+    host.set_vm_reuse(id.clone(), true);
+    let args = host.test_vec_obj::<i32>(&[1, 2])?;
+    host.with_test_contract_frame([1; 32].into(), Symbol::from_small_str("router"), || {
+        host.call(id_obj, sym.into(), args.into())?;
+        assert!(host.0.reusable_vms.borrow().contains_key(&id));
+        // A failed call rolls back the calls made within it, so the
+        // instances that have run in them can't be reused.
+        let res =
+            host.with_test_contract_frame([2; 32].into(), Symbol::from_small_str("fail"), || {
+                host.call(id_obj, sym.into(), args.into())?;
+                Err(host.err_general("failure"))
+            });
+        assert!(res.is_err());
+        assert!(host.0.reusable_vms.borrow().is_empty());
+        Ok(RawVal::VOID.into())
+    })?;
+    Ok(())
+}
+
+#[test]
+fn host_cost_models_from_config() -> Result<(), HostError> {
+    let default_budget = Budget::default();
+    let contract_models: Vec<ContractCostParamEntry> = ContractCostType::variants()
+        .iter()
+        .map(|ct| {
+            default_budget
+                .0
+                .borrow()
+                .cpu_insns
+                .get_cost_model(*ct)
+                .clone()
+        })
+        .collect();
+    let model = |const_term, linear_term| ContractCostParamEntry {
+        const_term,
+        linear_term,
+        ext: ExtensionPoint::V0,
+    };
+    let cpu_model =
+        |budget: &Budget, ht: HostCostType| budget.0.borrow().cpu_insns.get_cost_model(ht).clone();
+
+    // Without entries for them, the host cost types keep the default models.
+    let params = ContractCostParams(contract_models.clone().try_into()?);
+    let budget = Budget::from_configs(u64::MAX, u64::MAX, params.clone(), params);
+    for ht in HostCostType::variants() {
+        assert_eq!(cpu_model(&budget, ht), cpu_model(&default_budget, ht));
+    }
+
+    // The entries that follow the contract cost types override them, in the
+    // order of the host cost types.
+    let mut models = contract_models;
+    models.push(model(7, 0));
+    models.push(model(11, 3));
+    let params = ContractCostParams(models.try_into()?);
+    let budget = Budget::from_configs(u64::MAX, u64::MAX, params.clone(), params);
+    assert_eq!(cpu_model(&budget, HostCostType::Int256AddSub), model(7, 0));
+    assert_eq!(cpu_model(&budget, HostCostType::Int256Mul), model(11, 3));
+    assert_eq!(
+        cpu_model(&budget, HostCostType::Int256Div),
+        cpu_model(&default_budget, HostCostType::Int256Div)
+    );
+    budget.charge(HostCostType::Int256AddSub, None)?;
+    assert_eq!(
+        budget
+            .0
+            .borrow()
+            .cpu_insns
+            .get_count(HostCostType::Int256AddSub),
+        7
+    );
+    Ok(())
+}
+
 #[test]
 fn metered_xdr() -> Result<(), HostError> {
     let host = Host::test_host()
@@ -212,6 +377,94 @@ fn test_recursive_type_clone() -> Result<(), HostError> {
     Ok(())
 }
 
+#[test]
+fn budget_exceeded_diagnostics() -> Result<(), HostError> {
+    let host = Host::test_host().test_budget(1000, 100_000).enable_model(
+        ContractCostType::VecEntry,
+        10,
+        0,
+        0,
+        0,
+    );
+    host.set_diagnostic_level(DiagnosticLevel::Debug);
+    let outer: Hash = [1; 32].into();
+    let inner: Hash = [2; 32].into();
+
+    let res = host.with_test_contract_frame(outer, Symbol::from_small_str("router"), || {
+        host.with_budget(|b| b.batched_charge(ContractCostType::VecEntry, 50, None))?;
+        host.with_test_contract_frame(inner.clone(), Symbol::from_small_str("swap"), || {
+            host.with_budget(|b| b.batched_charge(ContractCostType::VecEntry, 60, None))?;
+            Ok(RawVal::VOID.into())
+        })
+    });
+    let err = res.unwrap_err();
+    assert_eq!(err.status, ScVmErrorCode::TrapCpuLimitExceeded.into());
+    let exceeded = err.budget_exceeded().unwrap();
+    assert_eq!(
+        exceeded.cost_type,
+        CostType::Contract(ContractCostType::VecEntry)
+    );
+    assert_eq!(exceeded.limit, 1000);
+    assert_eq!(exceeded.total_count, 1100);
+    assert_eq!(
+        exceeded.counts[CostType::from(ContractCostType::VecEntry).index()],
+        1100
+    );
+    // The innermost frame is the one blamed, not the outer one.
+    let frame = exceeded.frame.as_ref().unwrap();
+    assert_eq!(frame.contract_id, Some(inner));
+    assert_eq!(frame.function, "swap");
+
+    let budget_events = host
+        .get_events()?
+        .0
+        .iter()
+        .filter(|e| matches!(e.event, Event::StructuredDebug(_)))
+        .count();
+    assert_eq!(budget_events, 1);
+    Ok(())
+}
+
+#[test]
+fn cost_profile_tree() -> Result<(), HostError> {
+    let host = Host::test_host()
+        .test_budget(100_000, 100_000)
+        .enable_model(ContractCostType::VecEntry, 10, 0, 1, 0);
+    host.enable_cost_profiling();
+    let router: Hash = [1; 32].into();
+    let pool: Hash = [2; 32].into();
+    let charge = |n| host.with_budget(|b| b.batched_charge(ContractCostType::VecEntry, n, None));
+
+    host.with_test_contract_frame(router.clone(), Symbol::from_small_str("route"), || {
+        charge(1)?;
+        for _ in 0..2 {
+            host.with_test_contract_frame(pool.clone(), Symbol::from_small_str("swap"), || {
+                charge(5)?;
+                Ok(RawVal::VOID.into())
+            })?;
+        }
+        Ok(RawVal::VOID.into())
+    })?;
+
+    let profile = host.cost_profile();
+    assert_eq!(profile.len(), 1);
+    let root = &profile[0];
+    assert_eq!(root.contract_id, Some(router));
+    assert_eq!(root.function, "route");
+    assert_eq!(root.inclusive_cpu_insns, 110);
+    assert_eq!(root.self_cpu_insns, 10);
+    assert_eq!(root.inclusive_mem_bytes, 11);
+    assert_eq!(root.children.len(), 2);
+    for child in root.children.iter() {
+        assert_eq!(child.contract_id, Some(pool.clone()));
+        assert_eq!(child.function, "swap");
+        assert_eq!(child.self_cpu_insns, 50);
+        assert_eq!(child.inclusive_cpu_insns, 50);
+        assert!(child.children.is_empty());
+    }
+    Ok(())
+}
+
 // This test is a sanity check to make sure we didn't accidentally change the cost schedule.
 // If the cost schedule have changed, need to update this test by running
 // `UPDATE_EXPECT=true cargo test`
@@ -274,6 +527,18 @@ fn total_amount_charged_from_random_inputs() -> Result<(), HostError> {
         VmInstantiation          1              Some(147)      1000000        1100000        1000000             0                   1100000             0                   
         InvokeVmFunction         47             None           291964         12549          6212                0                   267                 0                   
         ChargeBudget             284            None           56232          0              198                 0                   0                   0                   
+        Int256AddSub             0              None           0              0              100                 0                   0                   0                   
+        Int256Mul                0              None           0              0              400                 0                   0                   0                   
+        Int256Div                0              None           0              0              2000                0                   0                   0                   
+        Int256Shift              0              None           0              0              50                  0                   0                   0                   
+        ComputeSecp256k1PubKey   0              None           0              0              60000               0                   0                   0                   
+        VerifyEcdsaSecp256k1Sig  0              None           0              0              1000000             0                   0                   0                   
+        RecoverEcdsaSecp256k1Key 0              None           0              0              1300000             0                   0                   0                   
+        ComputeSecp256r1PubKey   0              None           0              0              80000               0                   0                   0                   
+        VerifyEcdsaSecp256r1Sig  0              None           0              0              1500000             0                   0                   0                   
+        ComputeKeccak256Hash     0              Some(0)        0              0              3766                47                  40                  0                   
+        ComputeSha512Hash        0              Some(0)        0              0              2868                24                  72                  0                   
+        ComputeBlake2bHash       0              Some(0)        0              0              2390                19                  40                  0                   
         =====================================================================================================================================================================
 
     "#]]
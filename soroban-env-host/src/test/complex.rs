@@ -28,6 +28,9 @@ fn run_complex() -> Result<(), HostError> {
         timestamp: 1234,
         network_id: [7; 32],
         base_reserve: 1,
+        min_persistent_entry_expiration: 0,
+        min_temp_entry_expiration: 0,
+        max_entry_expiration: 0,
     };
     let id: Hash = [0; 32].into();
 
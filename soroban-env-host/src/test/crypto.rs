@@ -1,4 +1,10 @@
-use crate::{xdr::ScVal, Env, Host, HostError};
+use crate::{
+    budget::{AsBudget, HostCostType},
+    storage::Storage,
+    test::util::budget_with_host_cpu_models,
+    xdr::{ScHostFnErrorCode, ScVal},
+    BytesObject, Env, Host, HostError,
+};
 use hex::FromHex;
 
 /// crypto tests
@@ -25,6 +31,71 @@ fn sha256_test() -> Result<(), HostError> {
     Ok(())
 }
 
+#[test]
+fn keccak256_sha512_blake2b_test() -> Result<(), HostError> {
+    let host = Host::default();
+    let hash_bytes = |hash_obj: BytesObject| -> Result<Vec<u8>, HostError> {
+        let ScVal::Bytes(bytes) = host.from_host_val(hash_obj.to_raw())? else {
+            panic!("Wrong type")
+        };
+        Ok(bytes.as_vec().clone())
+    };
+    let obj_empty = host.test_bin_obj(&[])?;
+    let obj_abc = host.test_bin_obj(b"abc")?;
+
+    // The Ethereum test vectors, which differ from the SHA3-256 ones
+    let exp: Vec<u8> =
+        FromHex::from_hex("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470")
+            .unwrap();
+    assert_eq!(hash_bytes(host.compute_hash_keccak256(obj_empty)?)?, exp);
+    let exp: Vec<u8> =
+        FromHex::from_hex("4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45")
+            .unwrap();
+    assert_eq!(hash_bytes(host.compute_hash_keccak256(obj_abc)?)?, exp);
+
+    // From https://csrc.nist.gov/projects/cryptographic-standards-and-guidelines/example-values
+    let exp: Vec<u8> = FromHex::from_hex(
+        "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+         2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+    )
+    .unwrap();
+    assert_eq!(hash_bytes(host.compute_hash_sha512(obj_abc)?)?, exp);
+
+    let exp: Vec<u8> =
+        FromHex::from_hex("bddd813c634239723171ef3fee98579b94964e3bb1cb3e427262c8c068d52319")
+            .unwrap();
+    assert_eq!(hash_bytes(host.compute_hash_blake2b(obj_abc)?)?, exp);
+
+    // Each hash is charged under its own cost type, by the length hashed.
+    let tracker = |ty: HostCostType| host.as_budget().get_tracker(ty);
+    assert_eq!(tracker(HostCostType::ComputeKeccak256Hash), (2, Some(3)));
+    assert_eq!(tracker(HostCostType::ComputeSha512Hash), (1, Some(3)));
+    assert_eq!(tracker(HostCostType::ComputeBlake2bHash), (1, Some(3)));
+    Ok(())
+}
+
+#[test]
+fn keccak256_sha512_blake2b_configured_cost_models() -> Result<(), HostError> {
+    let budget = budget_with_host_cpu_models(&[
+        (HostCostType::ComputeKeccak256Hash, 10, 2),
+        (HostCostType::ComputeSha512Hash, 20, 3),
+        (HostCostType::ComputeBlake2bHash, 30, 4),
+    ])?;
+    let host = Host::with_storage_and_budget(Storage::default(), budget);
+    let obj_abc = host.test_bin_obj(b"abc")?;
+    host.compute_hash_keccak256(obj_abc)?;
+    host.compute_hash_sha512(obj_abc)?;
+    host.compute_hash_blake2b(obj_abc)?;
+
+    // The charges follow the models of the network configuration, linear in
+    // the length hashed.
+    let cpu = |ty: HostCostType| host.as_budget().0.borrow().cpu_insns.get_count(ty);
+    assert_eq!(cpu(HostCostType::ComputeKeccak256Hash), 10 + 2 * 3);
+    assert_eq!(cpu(HostCostType::ComputeSha512Hash), 20 + 3 * 3);
+    assert_eq!(cpu(HostCostType::ComputeBlake2bHash), 30 + 4 * 3);
+    Ok(())
+}
+
 #[test]
 fn ed25519_verify_test() -> Result<(), HostError> {
     let host = Host::default();
@@ -61,3 +132,168 @@ fn ed25519_verify_test() -> Result<(), HostError> {
     };
     Ok(())
 }
+
+#[test]
+fn ecdsa_secp256k1_verify_and_recover_test() -> Result<(), HostError> {
+    use k256::ecdsa::SigningKey;
+
+    let host = Host::default();
+    let signing_key = SigningKey::from_slice(&[0x11; 32]).unwrap();
+    let key = signing_key.verifying_key();
+    let digest = [0x42_u8; 32];
+    let (sig, recovery_id) = signing_key.sign_prehash_recoverable(&digest).unwrap();
+
+    let obj_pub = host.test_bin_obj(key.to_encoded_point(false).as_bytes())?;
+    let obj_pub_compressed = host.test_bin_obj(key.to_encoded_point(true).as_bytes())?;
+    let obj_digest = host.test_bin_obj(&digest)?;
+    let obj_sig = host.test_bin_obj(&sig.to_bytes())?;
+
+    // Both key encodings are accepted.
+    host.as_budget().reset_tracker();
+    host.verify_sig_ecdsa_secp256k1(obj_pub, obj_digest, obj_sig)?;
+    host.verify_sig_ecdsa_secp256k1(obj_pub_compressed, obj_digest, obj_sig)?;
+
+    // The recovered key is the uncompressed one.
+    let recovery_id: u32 = recovery_id.to_byte().into();
+    let recovered = host.recover_key_ecdsa_secp256k1(obj_digest, obj_sig, recovery_id.into())?;
+    assert_eq!(host.obj_cmp(recovered.into(), obj_pub.into())?, 0);
+
+    // Each of them is charged under its own cost type.
+    let tracker = |ty: HostCostType| host.as_budget().get_tracker(ty).0;
+    assert_eq!(tracker(HostCostType::ComputeSecp256k1PubKey), 2);
+    assert_eq!(tracker(HostCostType::VerifyEcdsaSecp256k1Sig), 2);
+    assert_eq!(tracker(HostCostType::RecoverEcdsaSecp256k1Key), 1);
+
+    // Now verify with the wrong digest
+    let obj_digest2 = host.test_bin_obj(&[0x43; 32])?;
+    assert!(host
+        .verify_sig_ecdsa_secp256k1(obj_pub, obj_digest2, obj_sig)
+        .is_err());
+    let recovered = host.recover_key_ecdsa_secp256k1(obj_digest2, obj_sig, recovery_id.into());
+    assert!(
+        recovered.map_or(true, |k| host.obj_cmp(k.into(), obj_pub.into()).unwrap()
+            != 0)
+    );
+
+    // And with malformed inputs
+    let obj_short = host.test_bin_obj(&digest[1..])?;
+    assert!(host
+        .verify_sig_ecdsa_secp256k1(obj_pub, obj_short, obj_sig)
+        .is_err());
+    let res = host.recover_key_ecdsa_secp256k1(obj_digest, obj_sig, 4_u32.into());
+    let code = ScHostFnErrorCode::InputArgsInvalid;
+    assert!(HostError::result_matches_err_status(res, code));
+    Ok(())
+}
+
+#[test]
+fn ecdsa_secp256k1_configured_cost_models() -> Result<(), HostError> {
+    use k256::ecdsa::SigningKey;
+
+    let budget = budget_with_host_cpu_models(&[
+        (HostCostType::ComputeSecp256k1PubKey, 10, 0),
+        (HostCostType::VerifyEcdsaSecp256k1Sig, 100, 0),
+        (HostCostType::RecoverEcdsaSecp256k1Key, 1000, 0),
+    ])?;
+    let host = Host::with_storage_and_budget(Storage::default(), budget);
+    let signing_key = SigningKey::from_slice(&[0x11; 32]).unwrap();
+    let digest = [0x42_u8; 32];
+    let (sig, recovery_id) = signing_key.sign_prehash_recoverable(&digest).unwrap();
+    let obj_pub = host.test_bin_obj(
+        signing_key
+            .verifying_key()
+            .to_encoded_point(false)
+            .as_bytes(),
+    )?;
+    let obj_digest = host.test_bin_obj(&digest)?;
+    let obj_sig = host.test_bin_obj(&sig.to_bytes())?;
+
+    host.verify_sig_ecdsa_secp256k1(obj_pub, obj_digest, obj_sig)?;
+    let recovery_id: u32 = recovery_id.to_byte().into();
+    host.recover_key_ecdsa_secp256k1(obj_digest, obj_sig, recovery_id.into())?;
+
+    // The charges follow the models of the network configuration.
+    let cpu = |ty: HostCostType| host.as_budget().0.borrow().cpu_insns.get_count(ty);
+    assert_eq!(cpu(HostCostType::ComputeSecp256k1PubKey), 10);
+    assert_eq!(cpu(HostCostType::VerifyEcdsaSecp256k1Sig), 100);
+    assert_eq!(cpu(HostCostType::RecoverEcdsaSecp256k1Key), 1000);
+    Ok(())
+}
+
+#[test]
+fn ecdsa_secp256r1_verify_test() -> Result<(), HostError> {
+    use p256::ecdsa::{signature::hazmat::PrehashSigner, Signature, SigningKey};
+
+    let host = Host::default();
+    let signing_key = SigningKey::from_slice(&[0x11; 32]).unwrap();
+    let obj_pub = host.test_bin_obj(
+        signing_key
+            .verifying_key()
+            .to_encoded_point(false)
+            .as_bytes(),
+    )?;
+
+    // The signatures aren't normalized by the signer, so look for a digest
+    // getting one with a high `s` to check both forms.
+    let (digest, high_sig, low_sig) = (0_u8..)
+        .find_map(|i| {
+            let digest = [i; 32];
+            let sig: Signature = signing_key.sign_prehash(&digest).unwrap();
+            sig.normalize_s().map(|low_sig| (digest, sig, low_sig))
+        })
+        .unwrap();
+    let obj_digest = host.test_bin_obj(&digest)?;
+    let obj_low_sig = host.test_bin_obj(&low_sig.to_bytes())?;
+    let obj_high_sig = host.test_bin_obj(&high_sig.to_bytes())?;
+
+    host.as_budget().reset_tracker();
+    host.verify_sig_ecdsa_secp256r1(obj_pub, obj_digest, obj_low_sig)?;
+    let res = host.verify_sig_ecdsa_secp256r1(obj_pub, obj_digest, obj_high_sig);
+    let code = ScHostFnErrorCode::InputArgsInvalid;
+    assert!(HostError::result_matches_err_status(res, code));
+
+    // The key is decoded, and charged for, before the signature is rejected.
+    let tracker = |ty: HostCostType| host.as_budget().get_tracker(ty).0;
+    assert_eq!(tracker(HostCostType::ComputeSecp256r1PubKey), 2);
+    assert_eq!(tracker(HostCostType::VerifyEcdsaSecp256r1Sig), 1);
+
+    // Now verify with the wrong digest
+    let mut digest2 = digest;
+    digest2[0] ^= 1;
+    let obj_digest2 = host.test_bin_obj(&digest2)?;
+    assert!(host
+        .verify_sig_ecdsa_secp256r1(obj_pub, obj_digest2, obj_low_sig)
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn ecdsa_secp256r1_configured_cost_models() -> Result<(), HostError> {
+    use p256::ecdsa::{signature::hazmat::PrehashSigner, Signature, SigningKey};
+
+    let budget = budget_with_host_cpu_models(&[
+        (HostCostType::ComputeSecp256r1PubKey, 10, 0),
+        (HostCostType::VerifyEcdsaSecp256r1Sig, 100, 0),
+    ])?;
+    let host = Host::with_storage_and_budget(Storage::default(), budget);
+    let signing_key = SigningKey::from_slice(&[0x11; 32]).unwrap();
+    let digest = [0x42_u8; 32];
+    let sig: Signature = signing_key.sign_prehash(&digest).unwrap();
+    let sig = sig.normalize_s().unwrap_or(sig);
+    let obj_pub = host.test_bin_obj(
+        signing_key
+            .verifying_key()
+            .to_encoded_point(false)
+            .as_bytes(),
+    )?;
+    let obj_digest = host.test_bin_obj(&digest)?;
+    let obj_sig = host.test_bin_obj(&sig.to_bytes())?;
+
+    host.verify_sig_ecdsa_secp256r1(obj_pub, obj_digest, obj_sig)?;
+
+    // The charges follow the models of the network configuration.
+    let cpu = |ty: HostCostType| host.as_budget().0.borrow().cpu_insns.get_count(ty);
+    assert_eq!(cpu(HostCostType::ComputeSecp256r1PubKey), 10);
+    assert_eq!(cpu(HostCostType::VerifyEcdsaSecp256r1Sig), 100);
+    Ok(())
+}
@@ -1,10 +1,14 @@
 use crate::{
-    events::{DebugEvent, Event, HostEvent},
+    budget::{AsBudget, LedgerIoType, LEDGER_IO_LIMIT_EXCEEDED},
+    events::{
+        ContractEventsLimits, DebugEvent, Event, EventFilter, HostEvent,
+        CONTRACT_EVENTS_LIMIT_EXCEEDED,
+    },
     xdr::{
         ContractEvent, ContractEventBody, ContractEventType, ContractEventV0, ExtensionPoint, Hash,
         ScMap, ScMapEntry, ScVal,
     },
-    ContractFunctionSet, Env, EnvBase, Host, HostError, RawVal, Symbol,
+    ContractFunctionSet, DiagnosticLevel, Env, EnvBase, Host, HostError, RawVal, Symbol,
 };
 use expect_test::expect;
 use std::rc::Rc;
@@ -72,6 +76,40 @@ fn contract_event() -> Result<(), HostError> {
     Ok(())
 }
 
+#[test]
+fn contract_event_trace() -> Result<(), HostError> {
+    let host = Host::default();
+    host.set_diagnostic_level(DiagnosticLevel::Debug);
+    let id = host.bytes_new_from_slice(&[0; 32])?;
+    host.register_test_contract(id, Rc::new(ContractWithSingleEvent {}))?;
+    let sym = Symbol::try_from_small_str("add").unwrap();
+    let args = host.test_vec_obj::<i32>(&[1, 2])?;
+    host.call(id, sym.into(), args.into())?;
+
+    // run `UPDATE_EXPECT=true cargo test` to update this.
+    let expected = expect![[r#"
+        call 0000000000000000000000000000000000000000000000000000000000000000:add(1i32, 2i32)
+          contract event from 0000000000000000000000000000000000000000000000000000000000000000: [0u32, 1u32] {1u32: 2u32}
+        return 0000000000000000000000000000000000000000000000000000000000000000:add -> ()
+    "#]];
+    expected.assert_eq(&host.get_events()?.trace().to_string());
+    Ok(())
+}
+
+#[test]
+fn host_error_renders_trace() -> Result<(), HostError> {
+    let host = Host::default();
+    host.set_diagnostic_level(DiagnosticLevel::Debug);
+    let err = host.err_general("failure");
+    let trace = err
+        .trace()
+        .expect("events captured with the error")
+        .to_string();
+    assert!(trace.contains("failure"));
+    assert!(format!("{:?}", err).contains(&format!("Events trace:\n{}", trace)));
+    Ok(())
+}
+
 pub struct ContractWithMultipleEvents;
 
 impl ContractFunctionSet for ContractWithMultipleEvents {
@@ -110,3 +148,138 @@ fn test_event_rollback() -> Result<(), HostError> {
     expected.assert_eq(&actual);
     Ok(())
 }
+
+#[test]
+fn contract_events_limits() -> Result<(), HostError> {
+    let contract_events_count = |host: &Host| -> Result<usize, HostError> {
+        let filter = EventFilter {
+            event_type: Some(ContractEventType::Contract),
+            ..Default::default()
+        };
+        Ok(host.get_events()?.filter(&filter).count())
+    };
+    let emit = |host: &Host| {
+        host.with_test_contract_frame(
+            Hash([0; 32]),
+            Symbol::try_from_small_str("emit").unwrap(),
+            || {
+                let topics = host.test_vec_obj::<u32>(&[0, 1])?;
+                host.contract_event(topics, RawVal::from(0u32))?;
+                Ok(RawVal::VOID.into())
+            },
+        )
+    };
+
+    let host = Host::default();
+    host.set_contract_events_limits(ContractEventsLimits {
+        max_count: 2,
+        ..Default::default()
+    });
+    emit(&host)?;
+    emit(&host)?;
+    let metadata_bytes = host
+        .as_budget()
+        .get_ledger_io_count(LedgerIoType::MetadataBytes);
+    let err = emit(&host).unwrap_err();
+    assert_eq!(err.status, CONTRACT_EVENTS_LIMIT_EXCEEDED.into());
+    assert_ne!(err.status, LEDGER_IO_LIMIT_EXCEEDED.into());
+    assert!(err.ledger_io_exceeded().is_none());
+    // Only the events within the limit have been recorded and charged.
+    assert_eq!(contract_events_count(&host)?, 2);
+    assert_eq!(
+        host.as_budget()
+            .get_ledger_io_count(LedgerIoType::MetadataBytes),
+        metadata_bytes
+    );
+
+    let host = Host::default();
+    host.set_contract_events_limits(ContractEventsLimits {
+        max_size_bytes: 1,
+        ..Default::default()
+    });
+    assert!(HostError::result_matches_err_status(
+        emit(&host),
+        CONTRACT_EVENTS_LIMIT_EXCEEDED
+    ));
+    assert_eq!(contract_events_count(&host)?, 0);
+    assert_eq!(
+        host.as_budget()
+            .get_ledger_io_count(LedgerIoType::MetadataBytes),
+        0
+    );
+    Ok(())
+}
+
+#[test]
+fn filter_events() -> Result<(), HostError> {
+    let host = Host::default();
+    let ids = [Hash([0; 32]), Hash([1; 32])];
+    for id in ids.iter() {
+        host.with_test_contract_frame(
+            id.clone(),
+            Symbol::try_from_small_str("emit").unwrap(),
+            || {
+                let topics = host.test_vec_obj::<u32>(&[0, 1])?;
+                host.record_contract_event(ContractEventType::Contract, topics, 2_u32.into())?;
+                Ok(RawVal::VOID.into())
+            },
+        )?;
+    }
+    let events = host.get_events()?;
+
+    let filter = EventFilter {
+        contract_id: Some(ids[1].clone()),
+        ..Default::default()
+    };
+    assert_eq!(events.filter(&filter).count(), 1);
+    assert_eq!(events.contract_events_of(&ids[1]).len(), 1);
+
+    let filter = EventFilter {
+        event_type: Some(ContractEventType::Contract),
+        topics: vec![None, Some(ScVal::U32(1))],
+        ..Default::default()
+    };
+    let matched: Vec<&HostEvent> = events.filter(&filter).collect();
+    assert_eq!(matched.len(), 2);
+    assert_eq!(matched[0].topics_as::<u32>(), Some(vec![0, 1]));
+    assert_eq!(matched[0].topics_as::<bool>(), None);
+    assert_eq!(matched[0].data_as::<u32>(), Some(2));
+
+    // The topic patterns only match a prefix of the topics.
+    let filter = EventFilter {
+        topics: vec![Some(ScVal::U32(0)), Some(ScVal::U32(1)), None],
+        ..Default::default()
+    };
+    assert_eq!(events.filter(&filter).count(), 0);
+
+    let filter = EventFilter {
+        failed_call: Some(true),
+        ..Default::default()
+    };
+    assert_eq!(events.filter(&filter).count(), 0);
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn events_to_json() -> Result<(), HostError> {
+    let host = Host::default();
+    host.with_test_contract_frame(
+        Hash([0; 32]),
+        Symbol::try_from_small_str("emit").unwrap(),
+        || {
+            let topics = host.test_vec_obj::<u32>(&[0])?;
+            host.record_contract_event(ContractEventType::Contract, topics, 1_u32.into())?;
+            host.record_debug_event(DebugEvent::new().msg("debug event 0"))?;
+            Ok(RawVal::VOID.into())
+        },
+    )?;
+    let json = serde_json::to_value(host.get_events()?).unwrap();
+    assert_eq!(json[0]["type"], "contract");
+    assert_eq!(json[0]["failed_call"], false);
+    assert_eq!(json[0]["contract_id"], hex::encode([0; 32]));
+    assert_eq!(json[0]["topics"].as_array().unwrap().len(), 1);
+    assert_eq!(json[1]["type"], "debug");
+    assert_eq!(json[1]["message"], "debug event 0");
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use soroban_env_common::{xdr::Hash, Env, RawVal, Symbol};
+
+use crate::{
+    fees::{
+        compute_fee_breakdown, compute_refund, compute_transaction_resource_fee,
+        compute_transaction_resources, FeeConfiguration, TransactionResources,
+    },
+    Host, HostError,
+};
+
+fn resources(metadata_size_bytes: u32) -> TransactionResources {
+    TransactionResources {
+        instructions: 30_000,
+        read_entries: 2,
+        write_entries: 1,
+        read_bytes: 2048,
+        write_bytes: 1000,
+        metadata_size_bytes,
+        transaction_size_bytes: 724,
+    }
+}
+
+fn fee_config() -> FeeConfiguration {
+    FeeConfiguration {
+        fee_per_instruction_increment: 100,
+        fee_per_read_entry: 10,
+        fee_per_write_entry: 20,
+        fee_per_read_1kb: 30,
+        fee_per_write_1kb: 40,
+        fee_per_historical_1kb: 50,
+        fee_per_metadata_1kb: 60,
+        fee_per_propagate_1kb: 70,
+    }
+}
+
+#[test]
+fn fee_breakdown() {
+    let breakdown = compute_fee_breakdown(&resources(3072), &fee_config());
+    assert_eq!(breakdown.compute_fee, 300);
+    assert_eq!(breakdown.read_entry_fee, 30);
+    assert_eq!(breakdown.write_entry_fee, 20);
+    assert_eq!(breakdown.read_bytes_fee, 60);
+    // Fees for fractional kilobytes are rounded up.
+    assert_eq!(breakdown.write_bytes_fee, 40);
+    assert_eq!(breakdown.historical_fee, 50);
+    assert_eq!(breakdown.metadata_fee, 180);
+    assert_eq!(breakdown.bandwidth_fee, 50);
+    assert_eq!(
+        compute_transaction_resource_fee(&resources(3072), &fee_config()),
+        (breakdown.total_fee(), 180)
+    );
+}
+
+#[test]
+fn fee_refund() {
+    let config = fee_config();
+    assert_eq!(
+        compute_refund(&resources(3072), &resources(1024), &config),
+        120
+    );
+    assert_eq!(
+        compute_refund(&resources(1024), &resources(1024), &config),
+        0
+    );
+    assert_eq!(
+        compute_refund(&resources(1024), &resources(3072), &config),
+        0
+    );
+}
+
+#[test]
+fn transaction_resources_from_host_run() -> Result<(), HostError> {
+    let host = Host::test_host_with_recording_footprint();
+    let id: Hash = [0; 32].into();
+    host.with_test_contract_frame(id, Symbol::from_small_str("put"), || {
+        let key: RawVal = Symbol::from_small_str("key").into();
+        host.put_contract_data(key, 1_u32.into())?;
+        host.has_contract_data(Symbol::from_small_str("missing").into())?;
+        let topics = host.test_vec_obj::<u32>(&[1])?;
+        Ok(host.contract_event(topics, 2_u32.into())?.into())
+    })?;
+    let (_, budget, _) = host.try_finish().map_err(|(_, e)| e)?;
+
+    let exact = compute_transaction_resources(&budget, 200, 0);
+    assert_eq!(exact.read_entries, 1);
+    assert_eq!(exact.write_entries, 1);
+    assert_eq!(exact.read_bytes, 0);
+    assert!(exact.write_bytes > 0);
+    // The written entry and the event.
+    assert!(exact.metadata_size_bytes > exact.write_bytes);
+    assert_eq!(exact.transaction_size_bytes, 200);
+
+    let with_margin = compute_transaction_resources(&budget, 200, 50);
+    assert_eq!(with_margin.read_entries, exact.read_entries);
+    assert_eq!(with_margin.write_entries, exact.write_entries);
+    assert_eq!(with_margin.write_bytes, exact.write_bytes * 3 / 2);
+    assert!(with_margin.instructions > exact.instructions);
+    Ok(())
+}
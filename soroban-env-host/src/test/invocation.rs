@@ -1,12 +1,17 @@
+use std::rc::Rc;
+
 use soroban_env_common::{
-    xdr::{ScHostContextErrorCode, ScVmErrorCode},
+    xdr::{
+        ContractCostType, ScHostContextErrorCode, ScHostFnErrorCode, ScSpecTypeDef,
+        ScSpecTypeOption, ScSpecTypeVec, ScVmErrorCode,
+    },
     RawVal,
 };
 
 use crate::{
-    events::{DebugArg, Event},
-    vm::Vm,
-    xdr::{Hash, ScHostObjErrorCode, ScStatusType, ScVal, ScVec},
+    events::{DebugArg, Event, EventFilter},
+    vm::{ContractSpec, FunctionSpec, Vm, MAX_SPEC_TYPE_DEPTH},
+    xdr::{ContractEventType, Hash, ScHostObjErrorCode, ScStatusType, ScSymbol, ScVal, ScVec},
     Env, Host, HostError, Status, Symbol, Tag,
 };
 use soroban_test_wasms::{ADD_I32, INVOKE_CONTRACT, VEC};
@@ -35,6 +40,101 @@ fn invoke_single_contract_function() -> Result<(), HostError> {
     Ok(())
 }
 
+#[test]
+fn contract_spec_from_wasm() -> Result<(), HostError> {
+    let host = Host::default();
+    let spec = ContractSpec::from_wasm(ADD_I32)?;
+    assert_eq!(
+        spec.functions,
+        vec![FunctionSpec {
+            name: "add".to_string(),
+            inputs: vec![
+                ("a".to_string(), ScSpecTypeDef::I32),
+                ("b".to_string(), ScSpecTypeDef::I32)
+            ],
+            outputs: vec![ScSpecTypeDef::I32],
+        }]
+    );
+    let vm = Vm::new(&host, [0; 32].into(), ADD_I32)?;
+    assert_eq!(vm.contract_spec()?, spec);
+    assert!(ContractSpec::from_wasm(b"not wasm").is_err());
+
+    // The spec is parsed once per instance, but its decoding is charged on
+    // every use.
+    let deser_count = || host.with_budget(|b| b.get_tracker(ContractCostType::ValDeser).0);
+    let count = deser_count();
+    let first = vm.metered_contract_spec(&host)?;
+    let second = vm.metered_contract_spec(&host)?;
+    assert!(Rc::ptr_eq(&first, &second));
+    assert_eq!(*first, spec);
+    assert_eq!(deser_count(), count + 2);
+    Ok(())
+}
+
+#[test]
+fn invoke_contract_with_args_validation() -> Result<(), HostError> {
+    let host = Host::test_host_with_recording_footprint();
+    let id_obj = host.register_test_contract_wasm(ADD_I32)?;
+    let sym = Symbol::try_from_small_str("add").unwrap();
+
+    // Without the validation, the wrongly typed arguments reach the contract.
+    let args = host.test_vec_obj::<u32>(&[1, 2])?;
+    assert!(host.call(id_obj, sym, args).is_err());
+
+    host.set_contract_args_validation(true);
+    let res = host.call(id_obj, sym, args);
+    assert!(HostError::result_matches_err_status(
+        res,
+        ScHostFnErrorCode::InputArgsWrongType
+    ));
+    let args = host.test_vec_obj::<i32>(&[1])?;
+    let res = host.call(id_obj, sym, args);
+    assert!(HostError::result_matches_err_status(
+        res,
+        ScHostFnErrorCode::InputArgsWrongLength
+    ));
+    let args = host.test_vec_obj::<i32>(&[1, 2])?;
+    let res = host.call(id_obj, sym, args)?;
+    assert_eq!(res.get_payload(), RawVal::from(3i32).get_payload());
+    Ok(())
+}
+
+#[test]
+fn args_validation_is_metered_and_bounded() -> Result<(), HostError> {
+    let host = Host::default();
+    let spec = |type_| FunctionSpec {
+        name: "f".to_string(),
+        inputs: vec![("a".to_string(), type_)],
+        outputs: vec![],
+    };
+    let visit_count = || host.with_budget(|b| b.get_tracker(ContractCostType::VisitObject).0);
+
+    // Every element of the argument is visited.
+    let vec_of_u32 = ScSpecTypeDef::Vec(Box::new(ScSpecTypeVec {
+        element_type: Box::new(ScSpecTypeDef::U32),
+    }));
+    let arg = ScVal::Vec(Some(host.test_scvec::<u32>(&[1, 2, 3])?));
+    let count = visit_count();
+    spec(vec_of_u32).check_args(&host, &[arg])?;
+    assert_eq!(visit_count(), count + 4);
+
+    // The types nested too deeply are rejected rather than recursed into.
+    let nested_option = |depth: u32| {
+        (0..depth).fold(ScSpecTypeDef::U32, |t, _| {
+            ScSpecTypeDef::Option(Box::new(ScSpecTypeOption {
+                value_type: Box::new(t),
+            }))
+        })
+    };
+    spec(nested_option(MAX_SPEC_TYPE_DEPTH - 1)).check_args(&host, &[ScVal::U32(1)])?;
+    let res = spec(nested_option(MAX_SPEC_TYPE_DEPTH)).check_args(&host, &[ScVal::U32(1)]);
+    assert!(HostError::result_matches_err_status(
+        res,
+        ScHostFnErrorCode::InputArgsInvalid
+    ));
+    Ok(())
+}
+
 fn invoke_cross_contract(diagnostics: bool) -> Result<(), HostError> {
     let host = Host::test_host_with_recording_footprint();
     if diagnostics {
@@ -63,6 +163,43 @@ fn invoke_cross_contract_with_diagnostics() -> Result<(), HostError> {
     invoke_cross_contract(true)
 }
 
+#[test]
+fn invoke_cross_contract_with_trace() -> Result<(), HostError> {
+    let host = Host::test_host_with_recording_footprint();
+    host.set_diagnostic_level(crate::DiagnosticLevel::Trace);
+    let id_obj = host.register_test_contract_wasm(VEC)?;
+    let sym = Symbol::try_from_small_str("vec_err").unwrap();
+    let args = host.test_vec_obj::<u32>(&[1])?;
+    host.try_call(id_obj, sym.into(), args.into())?;
+
+    // The host functions called by the contract are traced, including the
+    // failing one.
+    let vec_fn_calls = EventFilter {
+        event_type: Some(ContractEventType::Diagnostic),
+        topics: vec![
+            Some(ScVal::Symbol(ScSymbol("host_fn".try_into()?))),
+            Some(ScVal::Symbol(ScSymbol("vec".try_into()?))),
+        ],
+        ..Default::default()
+    };
+    assert!(host.get_events()?.filter(&vec_fn_calls).count() > 0);
+
+    // The failed contract call is only reported at the trace level.
+    let fn_errors = EventFilter {
+        event_type: Some(ContractEventType::Diagnostic),
+        topics: vec![Some(ScVal::Symbol(ScSymbol("fn_error".try_into()?)))],
+        ..Default::default()
+    };
+    assert_eq!(host.get_events()?.filter(&fn_errors).count(), 1);
+    let host = Host::test_host_with_recording_footprint();
+    host.set_diagnostic_level(crate::DiagnosticLevel::Debug);
+    let id_obj = host.register_test_contract_wasm(VEC)?;
+    let args = host.test_vec_obj::<u32>(&[1])?;
+    host.try_call(id_obj, sym.into(), args.into())?;
+    assert_eq!(host.get_events()?.filter(&fn_errors).count(), 0);
+    Ok(())
+}
+
 #[test]
 fn invoke_cross_contract_with_err() -> Result<(), HostError> {
     let host = Host::test_host_with_recording_footprint();
@@ -1,8 +1,16 @@
-use soroban_env_common::{xdr::ScBytes, Env};
+use std::rc::Rc;
+
+use soroban_env_common::{
+    xdr::{
+        ContractDataEntry, Hash, LedgerEntryData, LedgerKey, ScBytes, ScHostFnErrorCode,
+        ScHostStorageErrorCode, ScSymbol, ScVal,
+    },
+    Env, RawVal, Symbol,
+};
 
 use crate::{
     budget::Budget,
-    storage::{Footprint, Storage, StorageMap},
+    storage::{expiring_key_scval, Footprint, MemorySnapshot, Storage, StorageMap},
     Host, HostError, LedgerInfo,
 };
 
@@ -19,9 +27,202 @@ fn ledger_network_id() -> Result<(), HostError> {
         timestamp: 0,
         network_id: [7; 32],
         base_reserve: 0,
+        min_persistent_entry_expiration: 0,
+        min_temp_entry_expiration: 0,
+        max_entry_expiration: 0,
     });
     let obj = host.get_ledger_network_id()?;
     let np = host.visit_obj(obj, |np: &ScBytes| Ok(np.to_vec()))?;
     assert_eq!(np, vec![7; 32],);
     Ok(())
 }
+
+fn expiration_test_host(sequence_number: u32) -> Host {
+    let host = Host::test_host_with_recording_footprint();
+    host.set_ledger_info(LedgerInfo {
+        sequence_number,
+        min_persistent_entry_expiration: 10,
+        min_temp_entry_expiration: 5,
+        max_entry_expiration: 100,
+        ..Default::default()
+    });
+    host
+}
+
+#[test]
+fn contract_data_expires() -> Result<(), HostError> {
+    let host = expiration_test_host(100);
+    let id: Hash = [0; 32].into();
+    let key: RawVal = Symbol::from_small_str("key").into();
+    host.with_test_contract_frame(id.clone(), Symbol::from_small_str("put"), || {
+        Ok(host.put_contract_data(key, 1_u32.into())?.into())
+    })?;
+
+    // The entry is still live at the last ledger of its lifetime.
+    host.with_mut_ledger_info(|li| li.sequence_number = 110)?;
+    let res = host.with_test_contract_frame(id.clone(), Symbol::from_small_str("get"), || {
+        host.get_contract_data(key)
+    })?;
+    assert_eq!(res.get_payload(), RawVal::from(1_u32).get_payload());
+
+    // Reading it fails once it has expired, and it's no longer there for
+    // `has`, as with the expiring entries.
+    host.with_mut_ledger_info(|li| li.sequence_number = 111)?;
+    let res = host.with_test_contract_frame(id.clone(), Symbol::from_small_str("get"), || {
+        host.get_contract_data(key)
+    });
+    assert!(HostError::result_matches_err_status(
+        res,
+        ScHostStorageErrorCode::GetOnDeletedKey
+    ));
+    let res = host.with_test_contract_frame(id.clone(), Symbol::from_small_str("has"), || {
+        Ok(host.has_contract_data(key)?.into())
+    })?;
+    assert_eq!(res.get_payload(), RawVal::from_bool(false).get_payload());
+
+    // Which is distinct from reading an entry that doesn't exist.
+    let res = host.with_test_contract_frame(id, Symbol::from_small_str("get"), || {
+        host.get_contract_data(Symbol::from_small_str("missing").into())
+    });
+    assert!(HostError::result_matches_err_status(
+        res,
+        ScHostStorageErrorCode::MissingKeyInGet
+    ));
+    Ok(())
+}
+
+#[test]
+fn existing_contract_data_without_expiration_never_expires() -> Result<(), HostError> {
+    let id: Hash = [0; 32].into();
+    let key_scval = ScVal::Symbol(ScSymbol("key".as_bytes().to_vec().try_into()?));
+    let mut snapshot = MemorySnapshot::new();
+    let data = LedgerEntryData::ContractData(ContractDataEntry {
+        contract_id: id.clone(),
+        key: key_scval.clone(),
+        val: ScVal::U32(1),
+    });
+    snapshot.insert(Host::ledger_entry_from_data(data), None)?;
+    let storage = Storage::with_recording_footprint(Rc::new(snapshot));
+    let host = Host::with_storage_and_budget(storage, Budget::default());
+    host.set_ledger_info(LedgerInfo {
+        sequence_number: 100,
+        min_persistent_entry_expiration: 10,
+        min_temp_entry_expiration: 5,
+        max_entry_expiration: 100,
+        ..Default::default()
+    });
+
+    let key: RawVal = Symbol::from_small_str("key").into();
+    let new_key: RawVal = Symbol::from_small_str("new_key").into();
+    host.with_test_contract_frame(id.clone(), Symbol::from_small_str("put"), || {
+        host.put_contract_data(key, 2_u32.into())?;
+        Ok(host.put_contract_data(new_key, 3_u32.into())?.into())
+    })?;
+
+    // Only the entry created by the run gets the default expiration.
+    let budget = host.budget_cloned();
+    let mut storage = host.0.storage.borrow_mut();
+    let storage_key = host.storage_key_for_contract(id.clone(), key_scval);
+    assert_eq!(storage.get_expiration(&storage_key, &budget)?, None);
+    let storage_key = host.storage_key_for_contract(
+        id,
+        ScVal::Symbol(ScSymbol("new_key".as_bytes().to_vec().try_into()?)),
+    );
+    assert_eq!(storage.get_expiration(&storage_key, &budget)?, Some(110));
+    Ok(())
+}
+
+#[test]
+fn contract_data_bump() -> Result<(), HostError> {
+    let host = expiration_test_host(100);
+    let id: Hash = [0; 32].into();
+    let key: RawVal = Symbol::from_small_str("key").into();
+    host.with_test_contract_frame(id.clone(), Symbol::from_small_str("put"), || {
+        host.put_contract_data(key, 1_u32.into())?;
+        // Shorter than the current lifetime, so this is a no-op.
+        host.bump_contract_data(key, 5_u32.into())?;
+        Ok(host.bump_contract_data(key, 50_u32.into())?.into())
+    })?;
+
+    // Bumping past the maximum expiration is not allowed.
+    let res = host.with_test_contract_frame(id.clone(), Symbol::from_small_str("bump"), || {
+        Ok(host.bump_contract_data(key, 101_u32.into())?.into())
+    });
+    assert!(HostError::result_matches_err_status(
+        res,
+        ScHostFnErrorCode::InputArgsInvalid
+    ));
+
+    host.with_mut_ledger_info(|li| li.sequence_number = 150)?;
+    host.with_test_contract_frame(id.clone(), Symbol::from_small_str("get"), || {
+        host.get_contract_data(key)
+    })?;
+
+    let storage_key = host.storage_key_for_contract(
+        id,
+        ScVal::Symbol(ScSymbol("key".as_bytes().to_vec().try_into()?)),
+    );
+    let storage = host.0.storage.borrow();
+    let budget = host.budget_cloned();
+    assert_eq!(
+        storage
+            .expiration_map
+            .get::<Rc<LedgerKey>>(&storage_key, &budget)?,
+        Some(&150)
+    );
+    assert_eq!(
+        storage.bumps.get::<Rc<LedgerKey>>(&storage_key, &budget)?,
+        Some(&150)
+    );
+    Ok(())
+}
+
+#[test]
+fn expiring_contract_data() -> Result<(), HostError> {
+    let host = expiration_test_host(100);
+    let id: Hash = [0; 32].into();
+    let key: RawVal = Symbol::from_small_str("key").into();
+    host.with_test_contract_frame(id.clone(), Symbol::from_small_str("put"), || {
+        host.put_expiring_contract_data(key, 1_u32.into())?;
+        // Expiring and persistent entries don't share the key space.
+        assert!(!bool::from(host.has_contract_data(key)?));
+        host.put_contract_data(key, 2_u32.into())?;
+        let val = host.get_expiring_contract_data(key)?;
+        assert_eq!(val.get_payload(), RawVal::from(1_u32).get_payload());
+        Ok(RawVal::VOID.into())
+    })?;
+
+    // Expiring entry is gone after it expires, unlike the persistent one.
+    host.with_mut_ledger_info(|li| li.sequence_number = 106)?;
+    host.with_test_contract_frame(id.clone(), Symbol::from_small_str("get"), || {
+        assert!(!bool::from(host.has_expiring_contract_data(key)?));
+        // Reading it fails the same way as reading an expired persistent
+        // entry.
+        assert!(HostError::result_matches_err_status(
+            host.get_expiring_contract_data(key),
+            ScHostStorageErrorCode::GetOnDeletedKey
+        ));
+        let val = host.get_contract_data(key)?;
+        assert_eq!(val.get_payload(), RawVal::from(2_u32).get_payload());
+
+        // Writing an expired entry creates it anew.
+        host.put_expiring_contract_data(key, 3_u32.into())?;
+        assert!(bool::from(host.has_expiring_contract_data(key)?));
+        host.del_expiring_contract_data(key)?;
+        assert!(!bool::from(host.has_expiring_contract_data(key)?));
+        Ok(RawVal::VOID.into())
+    })?;
+
+    // The expiring entries can't be accessed through the persistent
+    // contract data functions, as contracts can't spell out their key.
+    host.with_test_contract_frame(id.clone(), Symbol::from_small_str("get"), || {
+        host.put_expiring_contract_data(key, 4_u32.into())?;
+        let expiring_key = expiring_key_scval(
+            id.clone(),
+            ScVal::Symbol(ScSymbol("key".as_bytes().try_into()?)),
+        )?;
+        assert!(host.to_host_val(&expiring_key).is_err());
+        Ok(RawVal::VOID.into())
+    })?;
+    Ok(())
+}
@@ -161,7 +161,7 @@ fn create_contract_using_parent_id_test() {
     });
 
     let child_id = sha256_hash_id_preimage(child_pre_image);
-    let child_wasm: &[u8] = b"70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4";
+    let child_wasm: &[u8] = ADD_I32;
     let upload_args = xdr::UploadContractWasmArgs {
         code: child_wasm.to_vec().try_into().unwrap(),
     };
@@ -247,8 +247,27 @@ fn create_contract_using_parent_id_test() {
 
 #[test]
 fn create_contract_from_source_account() {
-    let code: &[u8] = b"70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4";
-    test_create_contract_from_source_account(&test_host(), code);
+    test_create_contract_from_source_account(&test_host(), ADD_I32);
+}
+
+#[test]
+fn upload_invalid_wasm() {
+    let host = Host::test_host_with_recording_footprint();
+    host.set_source_account(generate_account_id());
+    let upload = |code: &[u8]| {
+        host.invoke_functions(vec![HostFunction {
+            args: HostFunctionArgs::UploadContractWasm(UploadContractWasmArgs {
+                code: code.to_vec().try_into().unwrap(),
+            }),
+            auth: Default::default(),
+        }])
+    };
+    // Code that isn't WASM at all.
+    assert!(upload(b"70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4").is_err());
+    // An empty module, which is valid WASM but is missing the environment
+    // metadata.
+    assert!(upload(&[0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00]).is_err());
+    assert!(upload(ADD_I32).is_ok());
 }
 
 pub(crate) fn sha256_hash_id_preimage<T: xdr::WriteXdr>(pre_image: T) -> xdr::Hash {
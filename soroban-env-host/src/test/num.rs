@@ -1,6 +1,13 @@
-use soroban_env_common::{num::*, xdr::ScVal, Compare, TryFromVal, TryIntoVal, I256};
+use soroban_env_common::{
+    num::*,
+    xdr::{ScHostFnErrorCode, ScVal, ScVmErrorCode},
+    Compare, Env, I128Val, I256Val, TryFromVal, TryIntoVal, U128Val, U256Val, I256,
+};
 
-use crate::{budget::AsBudget, Host, RawVal};
+use crate::{
+    budget::{AsBudget, HostCostType},
+    Host, HostError, RawVal,
+};
 use core::fmt::Debug;
 use std::cmp::Ordering;
 
@@ -197,3 +204,184 @@ fn test_num_rawval_scval_roundtrip_ordering() {
     }
     check_roundtrip_compare_ok::<I256>(&host, input_vec);
 }
+
+fn int_val<T: TryIntoVal<Host, RawVal>, V: TryFrom<RawVal>>(h: &Host, v: T) -> V
+where
+    V::Error: Debug,
+{
+    let raw: RawVal = v.try_into_val(h).unwrap();
+    V::try_from(raw).unwrap()
+}
+
+fn int_from_val<T: TryFromVal<Host, RawVal>, V: Into<RawVal>>(h: &Host, v: V) -> T {
+    let raw: RawVal = v.into();
+    raw.try_into_val(h).unwrap()
+}
+
+#[test]
+fn test_num_checked_arithmetic() -> Result<(), HostError> {
+    let host = Host::default();
+    let u256 = |u: U256| -> U256Val { int_val(&host, u) };
+    let i256 = |i: I256| -> I256Val { int_val(&host, i) };
+
+    // Small and object operands and results.
+    let res = host.u256_add(u256(U256::from(2_u32)), u256(U256::from(3_u32)))?;
+    assert!(!res.as_raw().is_object());
+    assert_eq!(int_from_val::<U256, _>(&host, res), U256::from(5_u32));
+    let res = host.u256_add(u256(MAX_SMALL_U256), u256(U256::ONE))?;
+    assert!(res.as_raw().is_object());
+    assert_eq!(int_from_val::<U256, _>(&host, res), MAX_SMALL_U256 + 1);
+    let res = host.u256_sub(u256(MAX_SMALL_U256 + 1), u256(U256::ONE))?;
+    assert!(!res.as_raw().is_object());
+    assert_eq!(int_from_val::<U256, _>(&host, res), MAX_SMALL_U256);
+
+    let res = host.u256_mul(u256(U256::MAX / 3), u256(U256::from(3_u32)))?;
+    assert_eq!(int_from_val::<U256, _>(&host, res), U256::MAX);
+    let res = host.i256_div(i256(I256::from(-7_i32)), i256(I256::from(2_i32)))?;
+    assert_eq!(int_from_val::<I256, _>(&host, res), I256::from(-3_i32));
+    let res = host.i256_rem(i256(I256::from(-7_i32)), i256(I256::from(2_i32)))?;
+    assert_eq!(int_from_val::<I256, _>(&host, res), I256::from(-1_i32));
+    let res = host.u256_pow(u256(U256::from(2_u32)), 255_u32.into())?;
+    assert_eq!(int_from_val::<U256, _>(&host, res), U256::ONE << 255);
+    let res = host.u256_shr(u256(U256::MAX), 255_u32.into())?;
+    assert_eq!(int_from_val::<U256, _>(&host, res), U256::ONE);
+    let res = host.i128_sub(int_val(&host, 1_i128), int_val(&host, i128::MAX))?;
+    assert_eq!(int_from_val::<i128, _>(&host, res), 2 - i128::MAX);
+    let res = host.u128_shl(int_val(&host, 1_u128), 127_u32.into())?;
+    assert_eq!(int_from_val::<u128, _>(&host, res), 1 << 127);
+
+    // Overflows.
+    let overflow = ScVmErrorCode::TrapIntegerOverflow;
+    let res = host.u256_add(u256(U256::MAX), u256(U256::ONE));
+    assert!(HostError::result_matches_err_status(res, overflow));
+    let res = host.u256_sub(u256(U256::ZERO), u256(U256::ONE));
+    assert!(HostError::result_matches_err_status(res, overflow));
+    let res = host.u256_pow(u256(U256::from(2_u32)), 256_u32.into());
+    assert!(HostError::result_matches_err_status(res, overflow));
+    let res = host.i256_mul(i256(I256::MIN), i256(I256::MINUS_ONE));
+    assert!(HostError::result_matches_err_status(res, overflow));
+    let res = host.i256_div(i256(I256::MIN), i256(I256::MINUS_ONE));
+    assert!(HostError::result_matches_err_status(res, overflow));
+    let u128_max: U128Val = int_val(&host, u128::MAX);
+    let res = host.u128_mul(u128_max, int_val(&host, 2_u128));
+    assert!(HostError::result_matches_err_status(res, overflow));
+    let i128_min: I128Val = int_val(&host, i128::MIN);
+    let res = host.i128_sub(i128_min, int_val(&host, 1_i128));
+    assert!(HostError::result_matches_err_status(res, overflow));
+
+    // Division by zero and out of range shifts.
+    let res = host.u256_div(u256(U256::ONE), u256(U256::ZERO));
+    let code = ScVmErrorCode::TrapDivisionByZero;
+    assert!(HostError::result_matches_err_status(res, code));
+    let res = host.i128_rem(int_val(&host, 1_i128), int_val(&host, 0_i128));
+    assert!(HostError::result_matches_err_status(res, code));
+    let res = host.i256_shl(i256(I256::ONE), 256_u32.into());
+    let code = ScHostFnErrorCode::InputArgsInvalid;
+    assert!(HostError::result_matches_err_status(res, code));
+    Ok(())
+}
+
+#[test]
+fn test_num_arithmetic_metering() -> Result<(), HostError> {
+    let host = Host::default();
+    let u256 = |u: U256| -> U256Val { int_val(&host, u) };
+    let tracker = |ty: HostCostType| host.as_budget().get_tracker(ty).0;
+    host.as_budget().reset_tracker();
+
+    host.u256_add(u256(U256::from(2_u32)), u256(U256::from(3_u32)))?;
+    host.i128_sub(int_val(&host, 1_i128), int_val(&host, 2_i128))?;
+    assert_eq!(tracker(HostCostType::Int256AddSub), 2);
+    host.u256_mul(u256(U256::MAX), u256(U256::ONE))?;
+    assert_eq!(tracker(HostCostType::Int256Mul), 1);
+    // Two multiplications per bit of the exponent 255.
+    host.u256_pow(u256(U256::from(2_u32)), 255_u32.into())?;
+    assert_eq!(tracker(HostCostType::Int256Mul), 1 + 16);
+    // Failed operations are charged too.
+    let res = host.u256_div(u256(U256::ONE), u256(U256::ZERO));
+    assert!(res.is_err());
+    assert_eq!(tracker(HostCostType::Int256Div), 1);
+    host.u128_shl(int_val(&host, 1_u128), 127_u32.into())?;
+    assert_eq!(tracker(HostCostType::Int256Shift), 1);
+
+    // The mul_div charges grow with the work: a product that overflows 256
+    // bits takes the 256 steps of the long division.
+    host.as_budget().reset_tracker();
+    let rounding = RoundingMode::Floor.into();
+    host.u256_mul_div(u256(U256::MAX), u256(U256::ONE), u256(U256::MAX), rounding)?;
+    assert_eq!(tracker(HostCostType::Int256Div), 2);
+    assert_eq!(tracker(HostCostType::Int256Shift), 4);
+    host.u256_mul_div(u256(U256::MAX), u256(U256::MAX), u256(U256::MAX), rounding)?;
+    assert_eq!(tracker(HostCostType::Int256Div), 2);
+    assert_eq!(tracker(HostCostType::Int256Shift), 4 + 4 + 4 * 256);
+    host.i128_mul_div(
+        int_val(&host, i128::MAX),
+        int_val(&host, i128::MAX),
+        int_val(&host, i128::MAX),
+        rounding,
+    )?;
+    assert_eq!(tracker(HostCostType::Int256Div), 4);
+    Ok(())
+}
+
+#[test]
+fn test_num_mul_div() -> Result<(), HostError> {
+    let host = Host::default();
+    let i128_val = |i: i128| -> I128Val { int_val(&host, i) };
+    let u256_val = |u: U256| -> U256Val { int_val(&host, u) };
+
+    // A 0.3% fee on 1001, with 7 decimals, rounded up.
+    let res = host.i128_mul_div(
+        i128_val(1001),
+        i128_val(30_000),
+        i128_val(10_000_000),
+        RoundingMode::Ceil.into(),
+    )?;
+    assert_eq!(int_from_val::<i128, _>(&host, res), 4);
+    let res = host.i128_mul_div(
+        i128_val(-7),
+        i128_val(3),
+        i128_val(2),
+        RoundingMode::Floor.into(),
+    )?;
+    assert_eq!(int_from_val::<i128, _>(&host, res), -11);
+    let res = host.i128_mul_div(
+        i128_val(i128::MAX),
+        i128_val(3),
+        i128_val(6),
+        RoundingMode::HalfEven.into(),
+    )?;
+    assert_eq!(int_from_val::<i128, _>(&host, res), i128::MAX / 2 + 1);
+    let res = host.u256_mul_div(
+        u256_val(U256::MAX),
+        u256_val(U256::MAX),
+        u256_val(U256::MAX),
+        RoundingMode::Ceil.into(),
+    )?;
+    assert_eq!(int_from_val::<U256, _>(&host, res), U256::MAX);
+
+    let res = host.i128_mul_div(
+        i128_val(i128::MAX),
+        i128_val(2),
+        i128_val(1),
+        RoundingMode::Floor.into(),
+    );
+    let code = ScVmErrorCode::TrapIntegerOverflow;
+    assert!(HostError::result_matches_err_status(res, code));
+    let res = host.u256_mul_div(
+        u256_val(U256::ONE),
+        u256_val(U256::ONE),
+        u256_val(U256::ZERO),
+        RoundingMode::Floor.into(),
+    );
+    let code = ScVmErrorCode::TrapDivisionByZero;
+    assert!(HostError::result_matches_err_status(res, code));
+    let res = host.u256_mul_div(
+        u256_val(U256::ONE),
+        u256_val(U256::ONE),
+        u256_val(U256::ONE),
+        3_u32.into(),
+    );
+    let code = ScHostFnErrorCode::InputArgsInvalid;
+    assert!(HostError::result_matches_err_status(res, code));
+    Ok(())
+}
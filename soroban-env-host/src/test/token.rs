@@ -51,6 +51,9 @@ impl TokenTest {
             timestamp: 123456,
             network_id: [5; 32],
             base_reserve: 5_000_000,
+            min_persistent_entry_expiration: 0,
+            min_temp_entry_expiration: 0,
+            max_entry_expiration: 0,
         });
         Self {
             host,
@@ -3,15 +3,15 @@ use std::rc::Rc;
 use rand::{thread_rng, RngCore};
 use soroban_env_common::{
     xdr::{
-        AccountEntry, AccountId, ContractCostType, ContractId, CreateContractArgs, HostFunction,
-        HostFunctionArgs, LedgerEntry, LedgerEntryData, LedgerKey, PublicKey, ScContractExecutable,
-        ScVal, ScVec, Uint256, UploadContractWasmArgs,
+        AccountEntry, AccountId, ContractCostParams, ContractCostType, ContractId,
+        CreateContractArgs, HostFunction, HostFunctionArgs, LedgerEntry, LedgerEntryData,
+        LedgerKey, PublicKey, ScContractExecutable, ScVal, ScVec, Uint256, UploadContractWasmArgs,
     },
     BytesObject, RawVal, TryIntoVal, VecObject,
 };
 
 use crate::{
-    budget::Budget,
+    budget::{Budget, CostType, HostCostType},
     storage::{test_storage::MockSnapshotSource, Storage},
     xdr, Host, HostError,
 };
@@ -45,6 +45,42 @@ pub(crate) fn generate_bytes_array() -> [u8; 32] {
     bytes
 }
 
+// Builds a budget out of a network configuration holding the default models
+// of all the cost types, except for the cpu models of the given host cost
+// types, set to `(const_term, linear_term)`.
+pub(crate) fn budget_with_host_cpu_models(
+    models: &[(HostCostType, i64, i64)],
+) -> Result<Budget, HostError> {
+    let default_budget = Budget::default();
+    let cost_params = |cpu: bool| -> Result<ContractCostParams, HostError> {
+        let mut entries = vec![];
+        for ct in CostType::variants() {
+            let budget = default_budget.0.borrow();
+            let dim = if cpu {
+                &budget.cpu_insns
+            } else {
+                &budget.mem_bytes
+            };
+            let mut entry = dim.get_cost_model(ct).clone();
+            if let Some((_, const_term, linear_term)) = models
+                .iter()
+                .find(|(ht, ..)| cpu && CostType::Host(*ht) == ct)
+            {
+                entry.const_term = *const_term;
+                entry.linear_term = *linear_term;
+            }
+            entries.push(entry);
+        }
+        Ok(ContractCostParams(entries.try_into()?))
+    };
+    Ok(Budget::from_configs(
+        u64::MAX,
+        u64::MAX,
+        cost_params(true)?,
+        cost_params(false)?,
+    ))
+}
+
 #[allow(dead_code)]
 impl Host {
     pub(crate) fn test_host() -> Self {
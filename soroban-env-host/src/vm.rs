@@ -10,13 +10,18 @@
 
 mod dispatch;
 mod func_info;
+mod module_cache;
+mod spec;
+
+pub use module_cache::ModuleCache;
+pub use spec::{ContractSpec, FunctionSpec, CONTRACT_SPEC_V0_SECTION_NAME, MAX_SPEC_TYPE_DEPTH};
 
 use crate::{
     host::{Frame, HostImpl},
     xdr::ContractCostType,
     HostError, VmCaller,
 };
-use std::{cell::RefCell, io::Cursor, rc::Rc};
+use std::{cell::RefCell, io::Cursor, rc::Rc, sync::Arc};
 
 use super::{xdr::Hash, Host, RawVal, Symbol};
 use func_info::HOST_FUNCTIONS;
@@ -27,8 +32,8 @@ use soroban_env_common::{
 };
 
 use wasmi::{
-    core::Value, Caller, Engine, Instance, Linker, Memory, Module, StepMeter, Store,
-    StoreContextMut,
+    core::{Value, ValueType},
+    Caller, Engine, Instance, Linker, Memory, Module, StepMeter, Store, StoreContextMut,
 };
 
 #[cfg(any(test, feature = "testutils"))]
@@ -75,12 +80,15 @@ impl StepMeter for HostImpl {
 pub struct Vm {
     #[allow(dead_code)]
     pub(crate) contract_id: Hash,
-    // TODO: consider moving store and possibly module to Host so they can be
-    // recycled across calls. Or possibly beyond, to be recycled across txs.
-    module: Module,
+    // TODO: consider moving store to Host so it can be recycled across calls.
+    // The module can be shared through a `ModuleCache`.
+    module: Arc<Module>,
     store: RefCell<Store<Host>>,
     instance: Instance,
     memory: Option<Memory>,
+    // The contract spec, parsed the first time the arguments of a call are
+    // checked against it.
+    spec: RefCell<Option<Rc<ContractSpec>>>,
 }
 
 /// Minimal description of a single function defined in a WASM module.
@@ -130,6 +138,34 @@ impl Vm {
         }
     }
 
+    pub(crate) fn wasmi_config() -> wasmi::Config {
+        let mut config = wasmi::Config::default();
+
+        // Turn off all optional wasm features.
+        config.wasm_multi_value(false);
+        config.wasm_mutable_global(false);
+        config.wasm_saturating_float_to_int(false);
+        config.wasm_sign_extension(false);
+
+        // This should always be true, and it enforces wasmi's notion of "deterministic only"
+        // execution, which excludes all floating point ops. Double check to be sure.
+        assert!(config.wasm_features().deterministic_only);
+        config
+    }
+
+    // Parses and validates a module, and checks its interface version. This
+    // isn't charged for, the callers cover it with the `VmInstantiation`
+    // charge.
+    pub(crate) fn parse_module(
+        host: &Host,
+        engine: &Engine,
+        module_wasm_code: &[u8],
+    ) -> Result<Module, HostError> {
+        let module = host.map_err(Module::new(engine, module_wasm_code))?;
+        Self::check_meta_section(host, &module)?;
+        Ok(module)
+    }
+
     /// Constructs a new instance of a [Vm] within the provided [Host],
     /// establishing a new execution context for a contract identified by
     /// `contract_id` with WASM bytecode provided in `module_wasm_code`.
@@ -159,24 +195,77 @@ impl Vm {
             Some(module_wasm_code.len() as u64),
         )?;
 
-        let mut config = wasmi::Config::default();
+        let engine = Engine::new(&Self::wasmi_config());
+        let module = Self::parse_module(host, &engine, module_wasm_code)?;
+        Self::instantiate(host, contract_id, &engine, Arc::new(module))
+    }
 
-        // Turn off all optional wasm features.
-        config.wasm_multi_value(false);
-        config.wasm_mutable_global(false);
-        config.wasm_saturating_float_to_int(false);
-        config.wasm_sign_extension(false);
+    /// Same as [Vm::new], but takes the module out of `cache` if it's there,
+    /// and adds it to `cache` otherwise.
+    ///
+    /// This is charged exactly like [Vm::new], so that the budget doesn't
+    /// depend on the state of the cache.
+    pub fn new_with_cache(
+        host: &Host,
+        contract_id: Hash,
+        wasm_hash: &Hash,
+        module_wasm_code: &[u8],
+        cache: &ModuleCache,
+    ) -> Result<Rc<Self>, HostError> {
+        host.charge_budget(
+            ContractCostType::VmInstantiation,
+            Some(module_wasm_code.len() as u64),
+        )?;
 
-        // This should always be true, and it enforces wasmi's notion of "deterministic only"
-        // execution, which excludes all floating point ops. Double check to be sure.
-        assert!(config.wasm_features().deterministic_only);
+        let module = cache.get_or_parse(host, wasm_hash, module_wasm_code)?;
+        Self::instantiate(host, contract_id, &cache.engine, module)
+    }
+
+    /// Checks that `module_wasm_code` can be instantiated and invoked as a
+    /// contract, so that unusable code is rejected when it's uploaded
+    /// rather than on its first invocation.
+    ///
+    /// This performs all the checks done by [Vm::new] (including the
+    /// resolution of the imports against the host functions), and also
+    /// checks that the exported functions only take and return [RawVal]s.
+    pub fn validate(host: &Host, module_wasm_code: &[u8]) -> Result<(), HostError> {
+        host.charge_budget(
+            ContractCostType::VmInstantiation,
+            Some(module_wasm_code.len() as u64),
+        )?;
 
-        let engine = Engine::new(&config);
-        let module = host.map_err(Module::new(&engine, module_wasm_code))?;
+        let engine = Engine::new(&Self::wasmi_config());
+        let module = Self::parse_module(host, &engine, module_wasm_code)?;
+        Self::check_exports(host, &module)?;
+        Self::link(host, &engine, &module)?;
+        Ok(())
+    }
 
-        Self::check_meta_section(host, &module)?;
+    fn check_exports(host: &Host, m: &Module) -> Result<(), HostError> {
+        for e in m.exports() {
+            if let wasmi::ExportItemKind::Func(f) = e.kind() {
+                if f.params().iter().any(|t| *t != ValueType::I64)
+                    || f.results().len() > 1
+                    || f.results().iter().any(|t| *t != ValueType::I64)
+                {
+                    return Err(host.err_status_msg(
+                        ScVmErrorCode::Function,
+                        "exported function has non-i64 parameters or results",
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
 
-        let mut store = Store::new(&engine, host.clone());
+    // Resolves the imports of the module against the host functions and
+    // instantiates it.
+    fn link(
+        host: &Host,
+        engine: &Engine,
+        module: &Module,
+    ) -> Result<(Store<Host>, Instance), HostError> {
+        let mut store = Store::new(engine, host.clone());
         store.set_step_meter(host.0.clone());
         let mut linker = <Linker<Host>>::new();
 
@@ -187,7 +276,7 @@ impl Vm {
             })?;
         }
 
-        let not_started_instance = host.map_err(linker.instantiate(&mut store, &module))?;
+        let not_started_instance = host.map_err(linker.instantiate(&mut store, module))?;
 
         let instance = not_started_instance
             .ensure_no_start(&mut store)
@@ -197,6 +286,16 @@ impl Vm {
                     "module contains disallowed start function",
                 )
             })?;
+        Ok((store, instance))
+    }
+
+    fn instantiate(
+        host: &Host,
+        contract_id: Hash,
+        engine: &Engine,
+        module: Arc<Module>,
+    ) -> Result<Rc<Self>, HostError> {
+        let (mut store, instance) = Self::link(host, engine, &module)?;
 
         let memory = if let Some(ext) = instance.get_export(&mut store, "memory") {
             ext.into_memory()
@@ -211,6 +310,7 @@ impl Vm {
             store,
             instance,
             memory,
+            spec: RefCell::new(None),
         }))
     }
 
@@ -325,6 +425,31 @@ impl Vm {
         Self::module_custom_section(&self.module, name)
     }
 
+    /// Returns the spec of the contract loaded into the [Vm], which is empty
+    /// if the contract doesn't embed one.
+    pub fn contract_spec(&self) -> Result<ContractSpec, HostError> {
+        match self.custom_section(CONTRACT_SPEC_V0_SECTION_NAME) {
+            Some(section) => ContractSpec::from_section(section),
+            None => Ok(ContractSpec::default()),
+        }
+    }
+
+    // Same as `contract_spec`, but the spec is only parsed once per instance.
+    // Decoding the spec section is charged on every call nonetheless, so
+    // that the budget doesn't depend on whether the instance is reused.
+    pub(crate) fn metered_contract_spec(&self, host: &Host) -> Result<Rc<ContractSpec>, HostError> {
+        let section_len = self
+            .custom_section(CONTRACT_SPEC_V0_SECTION_NAME)
+            .map_or(0, |section| section.len());
+        host.charge_budget(ContractCostType::ValDeser, Some(section_len as u64))?;
+        if let Some(spec) = self.spec.borrow().as_ref() {
+            return Ok(spec.clone());
+        }
+        let spec = Rc::new(self.contract_spec()?);
+        *self.spec.borrow_mut() = Some(spec.clone());
+        Ok(spec)
+    }
+
     /// Utility function that synthesizes a `VmCaller<Host>` configured to point
     /// to this VM's `Store` and `Instance`, and calls the provided function
     /// back with it. Mainly used for testing.
@@ -1,7 +1,8 @@
-use crate::{xdr::ContractCostType, Host, HostError, VmCaller, VmCallerEnv};
+use crate::{xdr::ContractCostType, Host, HostError, TryFromVal, VmCaller, VmCallerEnv};
 use crate::{
-    AddressObject, BytesObject, I128Object, I256Object, I64Object, MapObject, RawVal, Status,
-    StringObject, Symbol, SymbolObject, U128Object, U256Object, U32Val, U64Object, VecObject,
+    AddressObject, BytesObject, I128Object, I128Val, I256Object, I256Val, I64Object, MapObject,
+    RawVal, Status, StringObject, Symbol, SymbolObject, U128Object, U128Val, U256Object, U256Val,
+    U32Val, U64Object, VecObject,
 };
 use soroban_env_common::call_macro_with_all_host_functions;
 use wasmi::core::{FromValue, Trap, TrapCode::UnexpectedSignature, Value};
@@ -51,8 +52,9 @@ macro_rules! generate_dispatch_functions {
                 //     RawVal-wrappers expected by host functions, failing if
                 //     any conversions fail.
                 //  3. calls the host function
-                //  4. checks the result is Ok, or traps the VM on Err
-                //  5. converts the result back to an i64 for wasmi
+                //  4. emits a trace diagnostic event for the call, if enabled
+                //  5. checks the result is Ok, or traps the VM on Err
+                //  6. converts the result back to an i64 for wasmi
                 //
                 // It is embedded in two nested `$()*` pattern-repetition
                 // expanders that correspond to the pattern-repetition matchers
@@ -79,7 +81,21 @@ macro_rules! generate_dispatch_functions {
                     // happens to be a natural switching point for that: we have
                     // conversions to and from both RawVal and i64 / u64 for
                     // wasmi::Value.
-                    let res: Result<_, HostError> = host.$fn_id(&mut vmcaller, $(<$type as FromValue>::from_value(Value::I64($arg)).ok_or(UnexpectedSignature)?),*);
+                    $(let $arg = <$type as FromValue>::from_value(Value::I64($arg)).ok_or(UnexpectedSignature)?;)*
+                    let trace_start = host.host_fn_trace_start();
+                    let res: Result<_, HostError> = host.$fn_id(&mut vmcaller, $($arg),*);
+                    if let Some(start) = trace_start {
+                        host.host_fn_trace_diagnostics(
+                            stringify!($mod_name),
+                            stringify!($fn_id),
+                            start,
+                            || Ok(vec![$(RawVal::try_from_val(&host, &$arg)?),*]),
+                            || match &res {
+                                Ok(v) => Ok(RawVal::try_from_val(&host, v)?),
+                                Err(e) => Ok(e.status.to_raw()),
+                            },
+                        );
+                    }
                     let res: Value = match res {
                         Ok(ok) => ok.into(),
                         Err(hosterr) => {
@@ -0,0 +1,90 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+use wasmi::{Engine, Module};
+
+use super::Vm;
+use crate::{xdr::Hash, Host, HostError};
+
+/// A cache of parsed and validated WASM modules, keyed by the hash of their
+/// code, from which a [Host] can instantiate its [Vm]s instead of parsing
+/// the code of a contract on every call.
+///
+/// A [ModuleCache] can be shared by any number of hosts (including hosts on
+/// different threads) by cloning it: the clones refer to the same modules.
+///
+/// The cache doesn't affect metering: every instantiation is charged as if
+/// the module was parsed (see [Vm::new]), whether or not the module is in the
+/// cache already, so that the budget doesn't depend on the state of the
+/// cache.
+#[derive(Clone)]
+pub struct ModuleCache {
+    // The cached modules can only be instantiated with the engine they have
+    // been parsed with.
+    pub(crate) engine: Engine,
+    modules: Arc<Mutex<BTreeMap<Hash, Arc<Module>>>>,
+}
+
+impl Default for ModuleCache {
+    fn default() -> Self {
+        Self {
+            engine: Engine::new(&Vm::wasmi_config()),
+            modules: Default::default(),
+        }
+    }
+}
+
+impl ModuleCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of modules in the cache.
+    pub fn len(&self) -> usize {
+        self.modules.lock().map_or(0, |modules| modules.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn contains(&self, wasm_hash: &Hash) -> bool {
+        self.modules
+            .lock()
+            .map_or(false, |modules| modules.contains_key(wasm_hash))
+    }
+
+    /// Removes all the modules from the cache.
+    pub fn clear(&self) {
+        if let Ok(mut modules) = self.modules.lock() {
+            modules.clear();
+        }
+    }
+
+    // Returns the module with the given hash, parsing and validating
+    // `wasm_code` if the module isn't in the cache yet.
+    pub(crate) fn get_or_parse(
+        &self,
+        host: &Host,
+        wasm_hash: &Hash,
+        wasm_code: &[u8],
+    ) -> Result<Arc<Module>, HostError> {
+        if let Some(module) = self
+            .modules
+            .lock()
+            .ok()
+            .and_then(|modules| modules.get(wasm_hash).cloned())
+        {
+            return Ok(module);
+        }
+        // The lock isn't held while parsing, at the cost of parsing the same
+        // module more than once if several hosts miss it at the same time.
+        let module = Arc::new(Vm::parse_module(host, &self.engine, wasm_code)?);
+        if let Ok(mut modules) = self.modules.lock() {
+            modules.insert(wasm_hash.clone(), module.clone());
+        }
+        Ok(module)
+    }
+}
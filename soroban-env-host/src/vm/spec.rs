@@ -0,0 +1,183 @@
+use std::io::Cursor;
+
+use wasmi::{Engine, Module};
+
+use super::Vm;
+use crate::{
+    events::DebugError,
+    xdr::{ContractCostType, ReadXdr, ScHostFnErrorCode, ScSpecEntry, ScSpecTypeDef, ScVal},
+    Host, HostError,
+};
+
+/// Name of the WASM custom section the contract spec is embedded in.
+pub const CONTRACT_SPEC_V0_SECTION_NAME: &str = "contractspecv0";
+
+/// Maximum nesting depth of the argument types checked against the spec.
+pub const MAX_SPEC_TYPE_DEPTH: u32 = 32;
+
+/// The signature of a contract function, as declared in the contract spec.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FunctionSpec {
+    pub name: String,
+    /// The names and the types of the arguments, in order.
+    pub inputs: Vec<(String, ScSpecTypeDef)>,
+    pub outputs: Vec<ScSpecTypeDef>,
+}
+
+impl FunctionSpec {
+    // Returns an error describing the first argument that doesn't match the
+    // spec, if any.
+    pub(crate) fn check_args(&self, host: &Host, args: &[ScVal]) -> Result<(), HostError> {
+        if args.len() != self.inputs.len() {
+            return Err(host.err(
+                DebugError::new(ScHostFnErrorCode::InputArgsWrongLength)
+                    .msg("unexpected number of arguments for the contract function"),
+            ));
+        }
+        for (i, ((_, type_), arg)) in self.inputs.iter().zip(args.iter()).enumerate() {
+            if !value_matches_type(host, arg, type_, 0)? {
+                let mut err = DebugError::new(ScHostFnErrorCode::InputArgsWrongType);
+                err.event = err.event.msg(format!(
+                    "argument {} expected {:?}, got {:?}",
+                    i,
+                    type_.discriminant(),
+                    arg.discriminant()
+                ));
+                return Err(host.err(err));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The interface of a contract, i.e. the functions declared in its spec.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ContractSpec {
+    pub functions: Vec<FunctionSpec>,
+}
+
+impl ContractSpec {
+    /// Parses the contents of a contract spec custom section. Only the
+    /// function entries are kept.
+    pub fn from_section(section: &[u8]) -> Result<Self, HostError> {
+        let mut functions = vec![];
+        let mut cursor = Cursor::new(section);
+        for entry in ScSpecEntry::read_xdr_iter(&mut cursor) {
+            let entry = entry.map_err(|e| HostError::from(DebugError::from(e).status))?;
+            if let ScSpecEntry::FunctionV0(f) = entry {
+                functions.push(FunctionSpec {
+                    name: String::from_utf8_lossy(f.name.as_slice()).into_owned(),
+                    inputs: f
+                        .inputs
+                        .iter()
+                        .map(|input| {
+                            (
+                                String::from_utf8_lossy(input.name.as_slice()).into_owned(),
+                                input.type_.clone(),
+                            )
+                        })
+                        .collect(),
+                    outputs: f.outputs.to_vec(),
+                });
+            }
+        }
+        Ok(Self { functions })
+    }
+
+    /// Parses the spec of the contract with the given WASM code. The spec is
+    /// empty if the code doesn't have one.
+    pub fn from_wasm(module_wasm_code: &[u8]) -> Result<Self, HostError> {
+        let engine = Engine::new(&Vm::wasmi_config());
+        let module = Module::new(&engine, module_wasm_code)
+            .map_err(|e| HostError::from(DebugError::from(e).status))?;
+        match Vm::module_custom_section(&module, CONTRACT_SPEC_V0_SECTION_NAME) {
+            Some(section) => Self::from_section(section),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn function(&self, name: &str) -> Option<&FunctionSpec> {
+        self.functions.iter().find(|f| f.name == name)
+    }
+}
+
+// The user-defined types and the results aren't checked, as checking them
+// would require resolving the other entries of the spec. Every visited node
+// is charged as an object visit, and the types nested deeper than
+// `MAX_SPEC_TYPE_DEPTH` are rejected.
+fn value_matches_type(
+    host: &Host,
+    v: &ScVal,
+    t: &ScSpecTypeDef,
+    depth: u32,
+) -> Result<bool, HostError> {
+    if depth >= MAX_SPEC_TYPE_DEPTH {
+        return Err(host.err(
+            DebugError::new(ScHostFnErrorCode::InputArgsInvalid)
+                .msg("argument type is nested too deeply"),
+        ));
+    }
+    host.charge_budget(ContractCostType::VisitObject, None)?;
+    let matches = match t {
+        ScSpecTypeDef::Val | ScSpecTypeDef::Udt(_) | ScSpecTypeDef::Result(_) => true,
+        ScSpecTypeDef::Bool => matches!(v, ScVal::Bool(_)),
+        ScSpecTypeDef::Void => matches!(v, ScVal::Void),
+        ScSpecTypeDef::Status => matches!(v, ScVal::Status(_)),
+        ScSpecTypeDef::U32 => matches!(v, ScVal::U32(_)),
+        ScSpecTypeDef::I32 => matches!(v, ScVal::I32(_)),
+        ScSpecTypeDef::U64 => matches!(v, ScVal::U64(_)),
+        ScSpecTypeDef::I64 => matches!(v, ScVal::I64(_)),
+        ScSpecTypeDef::Timepoint => matches!(v, ScVal::Timepoint(_)),
+        ScSpecTypeDef::Duration => matches!(v, ScVal::Duration(_)),
+        ScSpecTypeDef::U128 => matches!(v, ScVal::U128(_)),
+        ScSpecTypeDef::I128 => matches!(v, ScVal::I128(_)),
+        ScSpecTypeDef::U256 => matches!(v, ScVal::U256(_)),
+        ScSpecTypeDef::I256 => matches!(v, ScVal::I256(_)),
+        ScSpecTypeDef::Bytes => matches!(v, ScVal::Bytes(_)),
+        ScSpecTypeDef::String => matches!(v, ScVal::String(_)),
+        ScSpecTypeDef::Symbol => matches!(v, ScVal::Symbol(_)),
+        ScSpecTypeDef::Address => matches!(v, ScVal::Address(_)),
+        ScSpecTypeDef::BytesN(n) => matches!(v, ScVal::Bytes(b) if b.len() == n.n as usize),
+        ScSpecTypeDef::Option(o) => {
+            matches!(v, ScVal::Void) || value_matches_type(host, v, &o.value_type, depth + 1)?
+        }
+        ScSpecTypeDef::Vec(t) => match v {
+            ScVal::Vec(Some(vec)) => {
+                for e in vec.iter() {
+                    if !value_matches_type(host, e, &t.element_type, depth + 1)? {
+                        return Ok(false);
+                    }
+                }
+                true
+            }
+            _ => false,
+        },
+        ScSpecTypeDef::Map(t) => match v {
+            ScVal::Map(Some(map)) => {
+                for e in map.iter() {
+                    if !value_matches_type(host, &e.key, &t.key_type, depth + 1)?
+                        || !value_matches_type(host, &e.val, &t.value_type, depth + 1)?
+                    {
+                        return Ok(false);
+                    }
+                }
+                true
+            }
+            _ => false,
+        },
+        ScSpecTypeDef::Tuple(t) => match v {
+            ScVal::Vec(Some(vec)) if vec.len() == t.value_types.len() => {
+                for (e, t) in vec.iter().zip(t.value_types.iter()) {
+                    if !value_matches_type(host, e, t, depth + 1)? {
+                        return Ok(false);
+                    }
+                }
+                true
+            }
+            _ => false,
+        },
+        #[allow(unreachable_patterns)]
+        _ => true,
+    };
+    Ok(matches)
+}